@@ -1 +1 @@
-rustc -O src/runner.rs -o MyStrategy.exe 2> compilation.log
+rustc -O --crate-type=lib --crate-name code_wars_rust_cgdk src/lib.rs 2> compilation.log && rustc -O -L . src/main.rs -o MyStrategy.exe 2>> compilation.log
@@ -1,3 +1,4 @@
 #!/bin/sh
 
-rustc -O src/runner.rs -o MyStrategy 2> compilation.log
+rustc -O --crate-type=lib --crate-name code_wars_rust_cgdk src/lib.rs 2> compilation.log &&
+rustc -O -L . src/main.rs -o MyStrategy 2>> compilation.log
@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+use model::{Action, ActionType, FacilityType, Game, World};
+
+#[derive(Clone, Debug)]
+pub struct ActionBudget {
+    interval: i32,
+    base_action_count: i32,
+    additional_action_count_per_control_center: i32,
+    tick_index: i32,
+    control_centers: i32,
+    remaining_cooldown_ticks: i32,
+    sent_ticks: VecDeque<i32>,
+}
+
+impl ActionBudget {
+    pub fn new(game: &Game) -> Self {
+        ActionBudget {
+            interval: game.action_detection_interval,
+            base_action_count: game.base_action_count,
+            additional_action_count_per_control_center: game.additional_action_count_per_control_center,
+            tick_index: 0,
+            control_centers: 0,
+            remaining_cooldown_ticks: 0,
+            sent_ticks: VecDeque::new(),
+        }
+    }
+
+    pub fn apply(&mut self, world: &World) {
+        self.tick_index = world.tick_index;
+
+        match world.get_my_player() {
+            Some(me) => {
+                self.remaining_cooldown_ticks = me.remaining_action_cooldown_ticks;
+                self.control_centers = world.facilities.iter()
+                    .filter(|v| v.kind == Some(FacilityType::ControlCenter) && v.owner_player_id == me.id)
+                    .count() as i32;
+            }
+            None => {
+                self.remaining_cooldown_ticks = 0;
+                self.control_centers = 0;
+            }
+        }
+
+        while self.sent_ticks.front().is_some_and(|&v| v + self.interval <= self.tick_index) {
+            self.sent_ticks.pop_front();
+        }
+    }
+
+    pub fn record(&mut self, action: &Action) {
+        match action.action {
+            None | Some(ActionType::None) => (),
+            Some(_) => self.sent_ticks.push_back(self.tick_index),
+        }
+    }
+
+    pub fn control_centers(&self) -> i32 {
+        self.control_centers
+    }
+
+    pub fn limit(&self) -> i32 {
+        self.base_action_count + self.additional_action_count_per_control_center * self.control_centers
+    }
+
+    pub fn used(&self) -> i32 {
+        self.sent_ticks.len() as i32
+    }
+
+    pub fn remaining(&self) -> i32 {
+        if self.remaining_cooldown_ticks > 0 {
+            0
+        } else {
+            (self.limit() - self.used()).max(0)
+        }
+    }
+
+    pub fn next_available_tick(&self) -> i32 {
+        let mut result = self.tick_index + self.remaining_cooldown_ticks;
+        let overflow = self.used() - self.limit();
+        if overflow >= 0 {
+            if let Some(&tick) = self.sent_ticks.get(overflow as usize) {
+                result = result.max(tick + self.interval);
+            }
+        }
+        result
+    }
+
+    pub fn ticks_until_available(&self) -> i32 {
+        self.next_available_tick() - self.tick_index
+    }
+}
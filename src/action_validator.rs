@@ -0,0 +1,142 @@
+use std::error::Error;
+use std::fmt;
+use model::{Action, ActionType, FacilityType, Game, Unit, World};
+use world_state::WorldState;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionError {
+    ActionCooldown { remaining_ticks: i32 },
+    InvalidGroup { group: i32, max_unit_group: i32 },
+    InvalidSelectionRect { left: f64, top: f64, right: f64, bottom: f64 },
+    NonPositiveScaleFactor { factor: f64 },
+    NegativeMaxSpeed { max_speed: f64 },
+    NuclearStrikeCooldown { remaining_ticks: i32 },
+    UnknownVehicle { vehicle_id: i64 },
+    ForeignVehicle { vehicle_id: i64 },
+    TargetOutOfVision { vehicle_id: i64, distance: f64, vision_range: f64 },
+    UnknownFacility { facility_id: i64 },
+    ForeignFacility { facility_id: i64 },
+    NotVehicleFactory { facility_id: i64 },
+}
+
+pub fn validate(action: &Action, state: &WorldState, world: &World, game: &Game) -> Result<(), ActionError> {
+    let kind = match action.action {
+        None | Some(ActionType::None) => return Ok(()),
+        Some(v) => v,
+    };
+
+    if let Some(me) = state.my_player() {
+        if me.remaining_action_cooldown_ticks > 0 {
+            return Err(ActionError::ActionCooldown { remaining_ticks: me.remaining_action_cooldown_ticks });
+        }
+    }
+
+    match kind {
+        ActionType::None => Ok(()),
+        ActionType::ClearAndSelect | ActionType::AddToSelection | ActionType::Deselect => {
+            if action.group != 0 {
+                validate_group(action.group, game)
+            } else if action.left > action.right || action.top > action.bottom {
+                Err(ActionError::InvalidSelectionRect {
+                    left: action.left,
+                    top: action.top,
+                    right: action.right,
+                    bottom: action.bottom,
+                })
+            } else {
+                Ok(())
+            }
+        }
+        ActionType::Assign | ActionType::Dismiss | ActionType::Disband => validate_group(action.group, game),
+        ActionType::Move | ActionType::Rotate => validate_max_speed(action.max_speed),
+        ActionType::Scale => {
+            if action.factor <= 0.0 {
+                Err(ActionError::NonPositiveScaleFactor { factor: action.factor })
+            } else {
+                validate_max_speed(action.max_speed)
+            }
+        }
+        ActionType::SetupVehicleProduction => {
+            let facility = state.facilities().iter().find(|v| v.id == action.facility_id)
+                .ok_or(ActionError::UnknownFacility { facility_id: action.facility_id })?;
+            if Some(facility.owner_player_id) != state.my_player_id() {
+                Err(ActionError::ForeignFacility { facility_id: facility.id })
+            } else if facility.kind != Some(FacilityType::VehicleFactory) {
+                Err(ActionError::NotVehicleFactory { facility_id: facility.id })
+            } else {
+                Ok(())
+            }
+        }
+        ActionType::TacticalNuclearStrike => {
+            if let Some(me) = state.my_player() {
+                if me.remaining_nuclear_strike_cooldown_ticks > 0 {
+                    return Err(ActionError::NuclearStrikeCooldown {
+                        remaining_ticks: me.remaining_nuclear_strike_cooldown_ticks,
+                    });
+                }
+            }
+            let vehicle = state.vehicle(action.vehicle_id)
+                .ok_or(ActionError::UnknownVehicle { vehicle_id: action.vehicle_id })?;
+            if Some(vehicle.player_id) != state.my_player_id() {
+                return Err(ActionError::ForeignVehicle { vehicle_id: vehicle.id });
+            }
+            let distance = vehicle.distance_to(action.x, action.y);
+            let vision_range = vehicle.vision_range * world.vehicle_factors(game, vehicle).vision;
+            if distance > vision_range {
+                Err(ActionError::TargetOutOfVision { vehicle_id: vehicle.id, distance, vision_range })
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+fn validate_group(group: i32, game: &Game) -> Result<(), ActionError> {
+    if group < 1 || group > game.max_unit_group {
+        Err(ActionError::InvalidGroup { group, max_unit_group: game.max_unit_group })
+    } else {
+        Ok(())
+    }
+}
+
+fn validate_max_speed(max_speed: f64) -> Result<(), ActionError> {
+    if max_speed < 0.0 {
+        Err(ActionError::NegativeMaxSpeed { max_speed })
+    } else {
+        Ok(())
+    }
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ActionError::ActionCooldown { remaining_ticks } => {
+                write!(f, "actions are on cooldown for {} ticks", remaining_ticks)
+            }
+            ActionError::InvalidGroup { group, max_unit_group } => {
+                write!(f, "group {} is out of range 1..={}", group, max_unit_group)
+            }
+            ActionError::InvalidSelectionRect { left, top, right, bottom } => {
+                write!(f, "invalid selection rect: left={} top={} right={} bottom={}", left, top, right, bottom)
+            }
+            ActionError::NonPositiveScaleFactor { factor } => write!(f, "scale factor is not positive: {}", factor),
+            ActionError::NegativeMaxSpeed { max_speed } => write!(f, "max speed is negative: {}", max_speed),
+            ActionError::NuclearStrikeCooldown { remaining_ticks } => {
+                write!(f, "nuclear strike is on cooldown for {} ticks", remaining_ticks)
+            }
+            ActionError::UnknownVehicle { vehicle_id } => write!(f, "unknown vehicle: {}", vehicle_id),
+            ActionError::ForeignVehicle { vehicle_id } => write!(f, "vehicle {} is not ours", vehicle_id),
+            ActionError::TargetOutOfVision { vehicle_id, distance, vision_range } => {
+                write!(f, "target is out of vision of vehicle {}: distance {} > vision range {}",
+                       vehicle_id, distance, vision_range)
+            }
+            ActionError::UnknownFacility { facility_id } => write!(f, "unknown facility: {}", facility_id),
+            ActionError::ForeignFacility { facility_id } => write!(f, "facility {} is not ours", facility_id),
+            ActionError::NotVehicleFactory { facility_id } => {
+                write!(f, "facility {} is not a vehicle factory", facility_id)
+            }
+        }
+    }
+}
+
+impl Error for ActionError {}
@@ -0,0 +1,272 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use runner::RunnerConfig;
+
+pub const USAGE: &str = "\
+Usage:
+    code_wars [HOST PORT TOKEN]
+    code_wars [OPTIONS]
+
+Options:
+    --host <HOST>                   server host [env: CODE_WARS_HOST] [default: 127.0.0.1]
+    --port <PORT>                   server port [env: CODE_WARS_PORT] [default: 31001]
+    --token <TOKEN>                 authentication token [env: CODE_WARS_TOKEN] [default: 0000000000000000]
+    --capture <PATH>                write all traffic into a capture file [env: CODE_WARS_CAPTURE]
+    --replay <PATH>                 play a capture file instead of connecting to the server [env: CODE_WARS_REPLAY]
+    --log <PATH>                    write runner warnings into a file instead of stderr [env: CODE_WARS_LOG]
+    --time-budget <MS>              warn when the strategy exceeds total time [env: CODE_WARS_TIME_BUDGET]
+    --tick-time-budget <MS>         warn when the strategy exceeds time on a tick [env: CODE_WARS_TICK_TIME_BUDGET]
+    --fallback-deadline <MS>        send an empty action when a tick takes longer [env: CODE_WARS_FALLBACK_DEADLINE]
+    --restart-after-panics <COUNT>  recreate the strategy after panics in a row [env: CODE_WARS_RESTART_AFTER_PANICS]
+    --validate-actions <MODE>       check actions before sending: off, warn or strict [env: CODE_WARS_VALIDATE_ACTIONS]
+                                    [default: off]
+    -h, --help                      print this message
+";
+
+const OPTIONS: [&str; 11] = [
+    "host",
+    "port",
+    "token",
+    "capture",
+    "replay",
+    "log",
+    "time-budget",
+    "tick-time-budget",
+    "fallback-deadline",
+    "restart-after-panics",
+    "validate-actions",
+];
+
+#[derive(Clone, Debug)]
+pub struct Args {
+    pub host: String,
+    pub port: u16,
+    pub token: String,
+    pub capture: Option<String>,
+    pub replay: Option<String>,
+    pub log: Option<String>,
+    pub config: RunnerConfig,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgsError {
+    Help,
+    UnknownOption(String),
+    MissingValue(String),
+    InvalidValue { name: String, value: String },
+    UnexpectedPositionalCount(usize),
+    MixedPositionalAndNamed,
+    ConflictingOptions(String, String),
+}
+
+impl Args {
+    pub fn from_env() -> Result<Self, ArgsError> {
+        Args::parse(std::env::args().skip(1), |name| std::env::var(name).ok())
+    }
+
+    pub fn parse<I, E>(args: I, env: E) -> Result<Self, ArgsError>
+        where I: IntoIterator<Item=String>, E: Fn(&str) -> Option<String> {
+        let mut named: Vec<(String, String)> = Vec::new();
+        let mut positional = Vec::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                return Err(ArgsError::Help);
+            }
+
+            if !arg.starts_with("--") {
+                positional.push(arg);
+                continue;
+            }
+
+            let (name, value) = match arg[2..].find('=') {
+                Some(position) => (arg[2..2 + position].to_string(), Some(arg[3 + position..].to_string())),
+                None => (arg[2..].to_string(), None),
+            };
+
+            if !OPTIONS.contains(&&name[..]) {
+                return Err(ArgsError::UnknownOption(arg));
+            }
+
+            let value = match value.or_else(|| args.next()) {
+                Some(v) => v,
+                None => return Err(ArgsError::MissingValue(name)),
+            };
+
+            named.push((name, value));
+        }
+
+        match positional.len() {
+            0 => (),
+            3 if named.is_empty() => {
+                named.push(("host".to_string(), positional[0].clone()));
+                named.push(("port".to_string(), positional[1].clone()));
+                named.push(("token".to_string(), positional[2].clone()));
+            }
+            3 => return Err(ArgsError::MixedPositionalAndNamed),
+            v => return Err(ArgsError::UnexpectedPositionalCount(v)),
+        }
+
+        let lookup = |name: &str| -> Option<(String, String)> {
+            named.iter().rev().find(|v| v.0 == name).map(|v| (format!("--{}", v.0), v.1.clone()))
+                .or_else(|| env(&env_name(name)).map(|v| (env_name(name), v)))
+        };
+
+        let capture = lookup("capture");
+        let replay = lookup("replay");
+        if let (Some(replay), Some(capture)) = (&replay, &capture) {
+            return Err(ArgsError::ConflictingOptions(replay.0.clone(), capture.0.clone()));
+        }
+
+        Ok(Args {
+            host: lookup("host").map_or_else(|| "127.0.0.1".to_string(), |v| v.1),
+            port: parse_value(lookup("port"))?.unwrap_or(31001),
+            token: lookup("token").map_or_else(|| "0000000000000000".to_string(), |v| v.1),
+            capture: capture.map(|v| v.1),
+            replay: replay.map(|v| v.1),
+            log: lookup("log").map(|v| v.1),
+            config: RunnerConfig {
+                tick_time_budget: parse_value(lookup("tick-time-budget"))?.map(Duration::from_millis),
+                total_time_budget: parse_value(lookup("time-budget"))?.map(Duration::from_millis),
+                fallback_deadline: parse_value(lookup("fallback-deadline"))?.map(Duration::from_millis),
+                restart_strategy_after_panics: parse_value(lookup("restart-after-panics"))?,
+                action_validation: parse_value(lookup("validate-actions"))?.unwrap_or_default(),
+            },
+        })
+    }
+}
+
+fn env_name(option: &str) -> String {
+    format!("CODE_WARS_{}", option.to_uppercase().replace('-', "_"))
+}
+
+fn parse_value<T: FromStr>(value: Option<(String, String)>) -> Result<Option<T>, ArgsError> {
+    match value {
+        Some((name, value)) => match value.parse() {
+            Ok(v) => Ok(Some(v)),
+            Err(_) => Err(ArgsError::InvalidValue { name, value }),
+        },
+        None => Ok(None),
+    }
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ArgsError::Help => write!(f, "help requested"),
+            ArgsError::UnknownOption(ref v) => write!(f, "unknown option: {}", v),
+            ArgsError::MissingValue(ref v) => write!(f, "missing value for option --{}", v),
+            ArgsError::InvalidValue { ref name, ref value } => write!(f, "invalid value for {}: {}", name, value),
+            ArgsError::UnexpectedPositionalCount(v) => {
+                write!(f, "expected exactly 3 positional arguments (host, port, token), but got {}", v)
+            }
+            ArgsError::MixedPositionalAndNamed => write!(f, "positional arguments can't be mixed with options"),
+            ArgsError::ConflictingOptions(ref a, ref b) => write!(f, "{} can't be used with {}", a, b),
+        }
+    }
+}
+
+impl Error for ArgsError {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use runner::ValidationMode;
+    use super::{Args, ArgsError};
+
+    fn parse(args: &[&str]) -> Result<Args, ArgsError> {
+        parse_with_env(args, &[])
+    }
+
+    fn parse_with_env(args: &[&str], env: &[(&str, &str)]) -> Result<Args, ArgsError> {
+        Args::parse(args.iter().map(|v| v.to_string()),
+                    |name| env.iter().find(|v| v.0 == name).map(|v| v.1.to_string()))
+    }
+
+    #[test]
+    fn defaults() {
+        let args = parse(&[]).unwrap();
+        assert_eq!(args.host, "127.0.0.1");
+        assert_eq!(args.port, 31001);
+        assert_eq!(args.token, "0000000000000000");
+        assert_eq!(args.capture, None);
+        assert_eq!(args.replay, None);
+        assert_eq!(args.config.action_validation, ValidationMode::Off);
+    }
+
+    #[test]
+    fn three_positional_arguments() {
+        let args = parse(&["localhost", "31002", "token"]).unwrap();
+        assert_eq!(args.host, "localhost");
+        assert_eq!(args.port, 31002);
+        assert_eq!(args.token, "token");
+    }
+
+    #[test]
+    fn one_or_two_positional_arguments_are_an_error() {
+        assert_eq!(parse(&["localhost"]).err(), Some(ArgsError::UnexpectedPositionalCount(1)));
+        assert_eq!(parse(&["localhost", "31002"]).err(), Some(ArgsError::UnexpectedPositionalCount(2)));
+    }
+
+    #[test]
+    fn positional_arguments_mixed_with_named_are_an_error() {
+        assert_eq!(parse(&["localhost", "31002", "token", "--log", "log.txt"]).err(),
+                   Some(ArgsError::MixedPositionalAndNamed));
+    }
+
+    #[test]
+    fn named_options_with_and_without_equals_sign() {
+        let args = parse(&["--host=localhost", "--port", "31002", "--tick-time-budget=20",
+                           "--validate-actions", "strict"]).unwrap();
+        assert_eq!(args.host, "localhost");
+        assert_eq!(args.port, 31002);
+        assert_eq!(args.config.tick_time_budget, Some(Duration::from_millis(20)));
+        assert_eq!(args.config.action_validation, ValidationMode::Strict);
+    }
+
+    #[test]
+    fn env_fallback() {
+        let args = parse_with_env(&[], &[("CODE_WARS_PORT", "31003"), ("CODE_WARS_RESTART_AFTER_PANICS", "3")])
+            .unwrap();
+        assert_eq!(args.port, 31003);
+        assert_eq!(args.config.restart_strategy_after_panics, Some(3));
+    }
+
+    #[test]
+    fn command_line_takes_precedence_over_env() {
+        let args = parse_with_env(&["--port", "31002"], &[("CODE_WARS_PORT", "31003")]).unwrap();
+        assert_eq!(args.port, 31002);
+        let args = parse_with_env(&["localhost", "31002", "token"], &[("CODE_WARS_PORT", "31003")]).unwrap();
+        assert_eq!(args.port, 31002);
+    }
+
+    #[test]
+    fn invalid_port() {
+        assert_eq!(parse(&["--port", "65536"]).err(),
+                   Some(ArgsError::InvalidValue { name: "--port".to_string(), value: "65536".to_string() }));
+        assert_eq!(parse_with_env(&[], &[("CODE_WARS_PORT", "port")]).err(),
+                   Some(ArgsError::InvalidValue { name: "CODE_WARS_PORT".to_string(), value: "port".to_string() }));
+    }
+
+    #[test]
+    fn unknown_option_and_missing_value() {
+        assert_eq!(parse(&["--hots", "localhost"]).err(), Some(ArgsError::UnknownOption("--hots".to_string())));
+        assert_eq!(parse(&["--port"]).err(), Some(ArgsError::MissingValue("port".to_string())));
+    }
+
+    #[test]
+    fn help() {
+        assert_eq!(parse(&["--port", "31002", "--help"]).err(), Some(ArgsError::Help));
+    }
+
+    #[test]
+    fn replay_with_capture_is_an_error() {
+        assert_eq!(parse(&["--replay", "a.capture", "--capture", "b.capture"]).err(),
+                   Some(ArgsError::ConflictingOptions("--replay".to_string(), "--capture".to_string())));
+        assert_eq!(parse_with_env(&["--replay", "a.capture"], &[("CODE_WARS_CAPTURE", "b.capture")]).err(),
+                   Some(ArgsError::ConflictingOptions("--replay".to_string(), "CODE_WARS_CAPTURE".to_string())));
+    }
+}
@@ -0,0 +1,198 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use remote_process_client::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+const RECEIVED: u8 = 0;
+const SENT: u8 = 1;
+
+pub struct Capture<C: io::Write> {
+    sink: Arc<Mutex<Option<C>>>,
+}
+
+impl Capture<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Capture::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<C: io::Write> Capture<C> {
+    pub fn new(sink: C) -> Self {
+        Capture { sink: Arc::new(Mutex::new(Some(sink))) }
+    }
+
+    pub fn reader<R: Read>(&self, inner: R) -> CaptureReader<R, C> {
+        CaptureReader { inner, capture: self.clone() }
+    }
+
+    pub fn writer<W: io::Write>(&self, inner: W) -> CaptureWriter<W, C> {
+        CaptureWriter { inner, capture: self.clone() }
+    }
+
+    fn record(&self, direction: u8, bytes: &[u8]) {
+        let mut sink = match self.sink.lock() {
+            Ok(v) => v,
+            Err(_) => return,
+        };
+        let result = match *sink {
+            Some(ref mut v) => write_frame(v, direction, bytes),
+            None => return,
+        };
+        if let Err(e) = result {
+            let _ = writeln!(&mut io::stderr(), "Capture error: {}, capture is stopped", e);
+            *sink = None;
+        }
+    }
+}
+
+fn write_frame<C: io::Write>(sink: &mut C, direction: u8, bytes: &[u8]) -> io::Result<()> {
+    sink.write_u8(direction)?;
+    sink.write_i32::<LittleEndian>(bytes.len() as i32)?;
+    sink.write_all(bytes)?;
+    sink.flush()
+}
+
+impl<C: io::Write> Clone for Capture<C> {
+    fn clone(&self) -> Self {
+        Capture { sink: self.sink.clone() }
+    }
+}
+
+pub struct CaptureReader<R, C: io::Write> {
+    inner: R,
+    capture: Capture<C>,
+}
+
+impl<R: Read, C: io::Write> Read for CaptureReader<R, C> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        if len > 0 {
+            self.capture.record(RECEIVED, &buf[..len]);
+        }
+        Ok(len)
+    }
+}
+
+pub struct CaptureWriter<W, C: io::Write> {
+    inner: W,
+    capture: Capture<C>,
+}
+
+impl<W: io::Write, C: io::Write> io::Write for CaptureWriter<W, C> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        if len > 0 {
+            self.capture.record(SENT, &buf[..len]);
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub struct Replay<R> {
+    inner: R,
+    remaining: usize,
+}
+
+impl Replay<BufReader<File>> {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Replay::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl<R: Read> Replay<R> {
+    pub fn new(inner: R) -> Self {
+        Replay { inner, remaining: 0 }
+    }
+
+    fn next_received_frame(&mut self) -> io::Result<bool> {
+        loop {
+            let mut direction = [0; 1];
+            if self.inner.read(&mut direction)? == 0 {
+                return Ok(false);
+            }
+
+            let len = self.inner.read_i32::<LittleEndian>()?;
+            if len < 0 {
+                return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                          format!("Replay error: negative frame length: {}", len)));
+            }
+
+            match direction[0] {
+                RECEIVED => {
+                    self.remaining = len as usize;
+                    return Ok(true);
+                }
+                SENT => {
+                    io::copy(&mut (&mut self.inner).take(len as u64), &mut io::sink())?;
+                }
+                v => {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                              format!("Replay error: invalid frame direction: {}", v)));
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for Replay<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.remaining == 0 {
+            if !self.next_received_frame()? {
+                return Ok(0);
+            }
+        }
+
+        let max = buf.len().min(self.remaining);
+        let len = self.inner.read(&mut buf[..max])?;
+        if len == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Replay error: truncated frame"));
+        }
+        self.remaining -= len;
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::io::{self, Read, Write};
+    use std::rc::Rc;
+    use super::Capture;
+
+    struct FailingSink {
+        writes: Rc<Cell<usize>>,
+    }
+
+    impl Write for FailingSink {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            self.writes.set(self.writes.get() + 1);
+            Err(io::Error::other("disk is full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn capture_error_does_not_fail_transport() {
+        let writes = Rc::new(Cell::new(0));
+        let capture = Capture::new(FailingSink { writes: writes.clone() });
+        let mut sent = Vec::new();
+        let mut buf = [0; 3];
+
+        capture.writer(&mut sent).write_all(&[1, 2, 3]).unwrap();
+        capture.reader(&[4, 5, 6][..]).read_exact(&mut buf).unwrap();
+        capture.writer(&mut sent).write_all(&[7]).unwrap();
+
+        assert_eq!(sent, vec![1, 2, 3, 7]);
+        assert_eq!(buf, [4, 5, 6]);
+        assert_eq!(writes.get(), 1);
+    }
+}
@@ -0,0 +1,104 @@
+use model::{Game, Vehicle, VehicleStats, VehicleType};
+
+const COUNT: usize = VehicleType::COUNT;
+
+#[derive(Clone, Debug)]
+pub struct Combat {
+    stats: [VehicleStats; COUNT],
+}
+
+impl Combat {
+    pub fn new(game: &Game) -> Self {
+        Combat { stats: game.all_stats() }
+    }
+
+    pub fn stats(&self, kind: VehicleType) -> &VehicleStats {
+        &self.stats[kind as usize]
+    }
+
+    pub fn can_attack(&self, attacker: VehicleType, target: VehicleType) -> bool {
+        let stats = self.stats(attacker);
+        let aerial_target = target.is_aerial();
+        stats.damage(aerial_target) > 0 && stats.attack_range(aerial_target) > 0.0
+    }
+
+    pub fn attack_range(&self, attacker: VehicleType, target: VehicleType) -> f64 {
+        self.stats(attacker).attack_range(target.is_aerial())
+    }
+
+    pub fn damage(&self, attacker: VehicleType, target: VehicleType) -> i32 {
+        if !self.can_attack(attacker, target) {
+            return 0;
+        }
+        let damage = self.stats(attacker).damage(target.is_aerial());
+        let defence = self.stats(target).defence(attacker.is_aerial());
+        (damage - defence).max(0)
+    }
+
+    pub fn damage_per_tick(&self, attacker: VehicleType, target: VehicleType) -> f64 {
+        let cooldown = self.stats(attacker).attack_cooldown_ticks.max(1);
+        self.damage(attacker, target) as f64 / cooldown as f64
+    }
+
+    pub fn hits_to_kill(&self, attacker: VehicleType, target: VehicleType) -> Option<i32> {
+        let damage = self.damage(attacker, target);
+        if damage > 0 {
+            Some((self.stats(target).durability + damage - 1) / damage)
+        } else {
+            None
+        }
+    }
+
+    pub fn ticks_to_kill(&self, attacker: VehicleType, target: VehicleType) -> Option<i32> {
+        self.hits_to_kill(attacker, target)
+            .map(|hits| (hits - 1).max(0) * self.stats(attacker).attack_cooldown_ticks)
+    }
+
+    pub fn damage_matrix(&self) -> [[i32; COUNT]; COUNT] {
+        matrix(|attacker, target| self.damage(attacker, target))
+    }
+
+    pub fn damage_per_tick_matrix(&self) -> [[f64; COUNT]; COUNT] {
+        matrix(|attacker, target| self.damage_per_tick(attacker, target))
+    }
+
+    pub fn ticks_to_kill_matrix(&self) -> [[Option<i32>; COUNT]; COUNT] {
+        matrix(|attacker, target| self.ticks_to_kill(attacker, target))
+    }
+
+    pub fn group_damage_per_tick(&self, attackers: &[usize; COUNT], targets: &[usize; COUNT]) -> f64 {
+        let targets_count: usize = targets.iter().sum();
+        if targets_count == 0 {
+            return 0.0;
+        }
+
+        let mut result = 0.0;
+        for attacker in VehicleType::ALL.iter() {
+            for target in VehicleType::ALL.iter() {
+                let share = targets[*target as usize] as f64 / targets_count as f64;
+                result += attackers[*attacker as usize] as f64 * share * self.damage_per_tick(*attacker, *target);
+            }
+        }
+        result
+    }
+}
+
+pub fn type_counts<'a, I: IntoIterator<Item=&'a Vehicle>>(vehicles: I) -> [usize; COUNT] {
+    let mut result = [0; COUNT];
+    for vehicle in vehicles {
+        if let Some(kind) = vehicle.kind {
+            result[kind as usize] += 1;
+        }
+    }
+    result
+}
+
+fn matrix<T: Copy + Default, F: Fn(VehicleType, VehicleType) -> T>(f: F) -> [[T; COUNT]; COUNT] {
+    let mut result = [[T::default(); COUNT]; COUNT];
+    for attacker in VehicleType::ALL.iter() {
+        for target in VehicleType::ALL.iter() {
+            result[*attacker as usize][*target as usize] = f(*attacker, *target);
+        }
+    }
+    result
+}
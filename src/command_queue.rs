@@ -0,0 +1,496 @@
+use std::cmp::Reverse;
+use std::collections::{HashSet, VecDeque};
+use model::{Action, ActionType, Player, Rect, VehicleType};
+use world_state::WorldState;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct CommandId(u64);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Selection {
+    Rect { rect: Rect, vehicle_type: Option<VehicleType> },
+    Group(i32),
+}
+
+impl Selection {
+    pub fn action(&self) -> Action {
+        match *self {
+            Selection::Rect { rect, vehicle_type } => Action::clear_and_select(rect, vehicle_type),
+            Selection::Group(group) => Action::select_group(group),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Command {
+    pub selection: Option<Selection>,
+    pub actions: Vec<Action>,
+    pub priority: i32,
+}
+
+impl Command {
+    pub fn new(selection: Selection, action: Action) -> Self {
+        Command { selection: Some(selection), actions: vec![action], priority: 0 }
+    }
+
+    pub fn without_selection(action: Action) -> Self {
+        Command { selection: None, actions: vec![action], priority: 0 }
+    }
+
+    pub fn then(mut self, action: Action) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Condition {
+    AtTick(i32),
+    GroupNear { group: i32, x: f64, y: f64, distance: f64 },
+    FacilityCaptured(i64),
+    EnemyNuclearStrikeLanded,
+    Completed(CommandId),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CommandStatus {
+    Waiting,
+    Pending,
+    Active,
+    Done,
+    Expired,
+    Cancelled,
+    Unknown,
+}
+
+#[derive(Clone, Debug)]
+struct Deferred {
+    id: CommandId,
+    command: Command,
+    condition: Condition,
+    timeout_ticks: Option<i32>,
+    expires_at: Option<i32>,
+    enemy_nuclear_strike_tick: Option<i32>,
+}
+
+#[derive(Clone, Debug)]
+struct Active {
+    id: CommandId,
+    actions: VecDeque<Action>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CommandQueue {
+    next_id: u64,
+    waiting: Vec<Deferred>,
+    pending: Vec<(CommandId, Command)>,
+    active: Option<Active>,
+    selection: Option<Selection>,
+    done: HashSet<CommandId>,
+    expired: HashSet<CommandId>,
+    cancelled: HashSet<CommandId>,
+}
+
+impl CommandQueue {
+    pub fn new() -> Self {
+        CommandQueue::default()
+    }
+
+    pub fn push(&mut self, command: Command) -> CommandId {
+        let id = self.next_id();
+        self.pending.push((id, command));
+        id
+    }
+
+    pub fn push_when(&mut self, command: Command, condition: Condition, timeout_ticks: Option<i32>) -> CommandId {
+        let id = self.next_id();
+        self.waiting.push(Deferred {
+            id,
+            command,
+            condition,
+            timeout_ticks,
+            expires_at: None,
+            enemy_nuclear_strike_tick: None,
+        });
+        id
+    }
+
+    pub fn update(&mut self, state: &WorldState) {
+        let tick_index = state.tick_index();
+        let strike_tick = state.opponent_player()
+            .map(|v| v.next_nuclear_strike_tick_index)
+            .filter(|&v| v >= 0);
+        let mut index = 0;
+
+        while index < self.waiting.len() {
+            let ready = {
+                let deferred = &mut self.waiting[index];
+                if deferred.expires_at.is_none() {
+                    deferred.expires_at = deferred.timeout_ticks.map(|v| tick_index + v);
+                }
+                if strike_tick.is_some() {
+                    deferred.enemy_nuclear_strike_tick = strike_tick;
+                } else if deferred.enemy_nuclear_strike_tick.is_some_and(|v| tick_index < v) {
+                    deferred.enemy_nuclear_strike_tick = None;
+                }
+                is_met(&deferred.condition, state, deferred.enemy_nuclear_strike_tick, &self.done)
+            };
+
+            let unreachable = is_unreachable(&self.waiting[index].condition, &self.expired, &self.cancelled);
+
+            if ready {
+                let deferred = self.waiting.remove(index);
+                self.pending.push((deferred.id, deferred.command));
+            } else if unreachable || self.waiting[index].expires_at.is_some_and(|v| tick_index >= v) {
+                let deferred = self.waiting.remove(index);
+                self.expired.insert(deferred.id);
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    pub fn status(&self, id: CommandId) -> CommandStatus {
+        if self.waiting.iter().any(|v| v.id == id) {
+            CommandStatus::Waiting
+        } else if self.pending.iter().any(|v| v.0 == id) {
+            CommandStatus::Pending
+        } else if self.active() == Some(id) {
+            CommandStatus::Active
+        } else if self.done.contains(&id) {
+            CommandStatus::Done
+        } else if self.expired.contains(&id) {
+            CommandStatus::Expired
+        } else if self.cancelled.contains(&id) {
+            CommandStatus::Cancelled
+        } else {
+            CommandStatus::Unknown
+        }
+    }
+
+    pub fn cancel(&mut self, id: CommandId) -> bool {
+        let len = self.pending.len() + self.waiting.len() + self.active.iter().count();
+        if self.active.as_ref().is_some_and(|v| v.id == id) {
+            self.active = None;
+        }
+        self.pending.retain(|v| v.0 != id);
+        self.waiting.retain(|v| v.id != id);
+        let cancelled = self.pending.len() + self.waiting.len() + self.active.iter().count() != len;
+        if cancelled {
+            self.cancelled.insert(id);
+        }
+        cancelled
+    }
+
+    pub fn clear(&mut self) {
+        self.cancelled.extend(self.waiting.drain(..).map(|v| v.id));
+        self.cancelled.extend(self.pending.drain(..).map(|v| v.0));
+        self.cancelled.extend(self.active.take().map(|v| v.id));
+    }
+
+    pub fn len(&self) -> usize {
+        self.waiting.len() + self.pending.len() + self.active.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn active(&self) -> Option<CommandId> {
+        self.active.as_ref().map(|v| v.id)
+    }
+
+    pub fn invalidate_selection(&mut self) {
+        self.selection = None;
+    }
+
+    pub fn act(&mut self, me: &Player, action: &mut Action) -> Option<CommandId> {
+        if me.remaining_action_cooldown_ticks > 0 {
+            return None;
+        }
+
+        while self.active.is_none() && !self.pending.is_empty() {
+            self.activate_next();
+        }
+
+        let (id, next, done) = {
+            let active = self.active.as_mut()?;
+            let next = active.actions.pop_front()?;
+            (active.id, next, active.actions.is_empty())
+        };
+
+        if done {
+            self.active = None;
+            self.done.insert(id);
+        }
+
+        self.track_selection(&next);
+        *action = next;
+        Some(id)
+    }
+
+    fn activate_next(&mut self) {
+        let index = match (0..self.pending.len()).max_by_key(|&i| (self.pending[i].1.priority, Reverse(self.pending[i].0))) {
+            Some(v) => v,
+            None => return,
+        };
+        let (id, command) = self.pending.remove(index);
+
+        let mut actions = VecDeque::with_capacity(command.actions.len() + 1);
+        if let Some(selection) = command.selection {
+            if self.selection.as_ref() != Some(&selection) {
+                actions.push_back(selection.action());
+            }
+        }
+        actions.extend(command.actions);
+
+        if actions.is_empty() {
+            self.done.insert(id);
+        } else {
+            self.active = Some(Active { id, actions });
+        }
+    }
+
+    fn next_id(&mut self) -> CommandId {
+        let id = CommandId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    fn track_selection(&mut self, action: &Action) {
+        match action.action {
+            Some(ActionType::ClearAndSelect) if action.group != 0 => {
+                self.selection = Some(Selection::Group(action.group));
+            }
+            Some(ActionType::ClearAndSelect) | Some(ActionType::AddToSelection) | Some(ActionType::Deselect) | Some(ActionType::Assign)
+            | Some(ActionType::Dismiss) | Some(ActionType::Disband) => self.selection = None,
+            _ => (),
+        }
+    }
+}
+
+fn is_unreachable(condition: &Condition, expired: &HashSet<CommandId>, cancelled: &HashSet<CommandId>) -> bool {
+    match *condition {
+        Condition::Completed(id) => expired.contains(&id) || cancelled.contains(&id),
+        Condition::All(ref conditions) => conditions.iter().any(|v| is_unreachable(v, expired, cancelled)),
+        Condition::Any(ref conditions) => {
+            !conditions.is_empty() && conditions.iter().all(|v| is_unreachable(v, expired, cancelled))
+        }
+        _ => false,
+    }
+}
+
+fn is_met(condition: &Condition, state: &WorldState, enemy_nuclear_strike_tick: Option<i32>,
+          done: &HashSet<CommandId>) -> bool {
+    match *condition {
+        Condition::AtTick(tick_index) => state.tick_index() >= tick_index,
+        Condition::GroupNear { group, x, y, distance } => state.group_extent(group)
+            .is_some_and(|v| (v.centroid_x - x).hypot(v.centroid_y - y) <= distance),
+        Condition::FacilityCaptured(facility_id) => state.facilities().iter()
+            .any(|v| v.id == facility_id && Some(v.owner_player_id) == state.my_player_id()),
+        Condition::EnemyNuclearStrikeLanded => enemy_nuclear_strike_tick.is_some_and(|v| state.tick_index() >= v),
+        Condition::Completed(id) => done.contains(&id),
+        Condition::All(ref conditions) => {
+            conditions.iter().all(|v| is_met(v, state, enemy_nuclear_strike_tick, done))
+        }
+        Condition::Any(ref conditions) => {
+            conditions.iter().any(|v| is_met(v, state, enemy_nuclear_strike_tick, done))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use model::{Action, Rect};
+    use test_fixtures::{player, players, world};
+    use world_state::WorldState;
+    use super::{Command, CommandId, CommandQueue, CommandStatus, Condition, Selection};
+
+    fn state(tick_index: i32) -> WorldState {
+        let mut state = WorldState::new();
+        state.apply(&world(tick_index, players(), Vec::new(), Vec::new()));
+        state
+    }
+
+    fn apply_strike(state: &mut WorldState, tick_index: i32, strike_tick_index: i32) {
+        let mut opponent = player(2, false);
+        opponent.next_nuclear_strike_tick_index = strike_tick_index;
+        state.apply(&world(tick_index, vec![player(1, true), opponent], Vec::new(), Vec::new()));
+    }
+
+    fn act_all(queue: &mut CommandQueue) -> Vec<(Option<CommandId>, Action)> {
+        let me = player(1, true);
+        let mut result = Vec::new();
+        loop {
+            let mut action = Action::default();
+            match queue.act(&me, &mut action) {
+                Some(id) => result.push((Some(id), action)),
+                None => return result,
+            }
+        }
+    }
+
+    #[test]
+    fn rect_selection_is_sent_for_each_command() {
+        let mut queue = CommandQueue::new();
+        let rect = Selection::Rect { rect: Rect::new(0.0, 0.0, 100.0, 100.0), vehicle_type: None };
+        queue.push(Command::new(rect.clone(), Action::move_by(500.0, 0.0, 0.0)));
+        queue.push(Command::new(rect.clone(), Action::move_by(0.0, 500.0, 0.0)));
+
+        let actions: Vec<Action> = act_all(&mut queue).into_iter().map(|v| v.1).collect();
+        assert_eq!(actions, vec![
+            rect.action(),
+            Action::move_by(500.0, 0.0, 0.0),
+            rect.action(),
+            Action::move_by(0.0, 500.0, 0.0),
+        ]);
+    }
+
+    #[test]
+    fn group_selection_is_skipped_when_already_selected() {
+        let mut queue = CommandQueue::new();
+        queue.push(Command::new(Selection::Group(2), Action::move_by(1.0, 0.0, 0.0)));
+        queue.push(Command::new(Selection::Group(2), Action::move_by(2.0, 0.0, 0.0)));
+        queue.push(Command::new(Selection::Group(3), Action::move_by(3.0, 0.0, 0.0)));
+        queue.push(Command::without_selection(Action::assign(4)));
+        queue.push(Command::new(Selection::Group(3), Action::move_by(4.0, 0.0, 0.0)));
+
+        let actions: Vec<Action> = act_all(&mut queue).into_iter().map(|v| v.1).collect();
+        assert_eq!(actions, vec![
+            Action::select_group(2),
+            Action::move_by(1.0, 0.0, 0.0),
+            Action::move_by(2.0, 0.0, 0.0),
+            Action::select_group(3),
+            Action::move_by(3.0, 0.0, 0.0),
+            Action::assign(4),
+            Action::select_group(3),
+            Action::move_by(4.0, 0.0, 0.0),
+        ]);
+    }
+
+    #[test]
+    fn higher_priority_goes_first_and_equal_priorities_keep_order() {
+        let mut queue = CommandQueue::new();
+        let low = queue.push(Command::without_selection(Action::move_by(1.0, 0.0, 0.0)));
+        let high = queue.push(Command::without_selection(Action::move_by(2.0, 0.0, 0.0)).with_priority(10));
+        let also_low = queue.push(Command::without_selection(Action::move_by(3.0, 0.0, 0.0)));
+        let highest = queue.push(Command::without_selection(Action::move_by(4.0, 0.0, 0.0))
+            .then(Action::scale(0.0, 0.0, 0.5))
+            .with_priority(20));
+
+        let ids: Vec<Option<CommandId>> = act_all(&mut queue).into_iter().map(|v| v.0).collect();
+        assert_eq!(ids, vec![Some(highest), Some(highest), Some(high), Some(low), Some(also_low)]);
+        assert_eq!(queue.status(highest), CommandStatus::Done);
+    }
+
+    #[test]
+    fn nothing_is_sent_during_action_cooldown() {
+        let mut queue = CommandQueue::new();
+        queue.push(Command::without_selection(Action::move_by(1.0, 0.0, 0.0)));
+        let mut me = player(1, true);
+        me.remaining_action_cooldown_ticks = 1;
+        let mut action = Action::default();
+
+        assert_eq!(queue.act(&me, &mut action), None);
+        assert_eq!(action.action, None);
+        assert_eq!(act_all(&mut queue).len(), 1);
+    }
+
+    #[test]
+    fn cancelled_command_expires_dependents() {
+        let mut queue = CommandQueue::new();
+        let first = queue.push_when(Command::without_selection(Action::assign(1)), Condition::AtTick(10), None);
+        let second = queue.push_when(Command::without_selection(Action::dismiss(1)),
+                                     Condition::Completed(first), None);
+
+        assert!(queue.cancel(first));
+        assert!(!queue.cancel(first));
+        assert_eq!(queue.status(first), CommandStatus::Cancelled);
+
+        queue.update(&state(0));
+        assert_eq!(queue.status(second), CommandStatus::Expired);
+        assert!(queue.is_empty());
+
+        let mut action = Action::default();
+        assert_eq!(queue.act(&player(1, true), &mut action), None);
+    }
+
+    #[test]
+    fn waits_for_landed_enemy_nuclear_strike() {
+        let mut state = WorldState::new();
+        let mut queue = CommandQueue::new();
+        let id = queue.push_when(Command::without_selection(Action::move_by(1.0, 0.0, 0.0)),
+                                 Condition::EnemyNuclearStrikeLanded, None);
+
+        apply_strike(&mut state, 10, 40);
+        queue.update(&state);
+        apply_strike(&mut state, 39, 40);
+        queue.update(&state);
+        assert_eq!(queue.status(id), CommandStatus::Waiting);
+
+        apply_strike(&mut state, 40, -1);
+        queue.update(&state);
+        assert_eq!(queue.status(id), CommandStatus::Pending);
+    }
+
+    #[test]
+    fn cancelled_enemy_nuclear_strike_is_forgotten() {
+        let mut state = WorldState::new();
+        let mut queue = CommandQueue::new();
+        let id = queue.push_when(Command::without_selection(Action::move_by(1.0, 0.0, 0.0)),
+                                 Condition::EnemyNuclearStrikeLanded, None);
+
+        apply_strike(&mut state, 10, 40);
+        queue.update(&state);
+        for tick_index in 20..60 {
+            apply_strike(&mut state, tick_index, -1);
+            queue.update(&state);
+        }
+        assert_eq!(queue.status(id), CommandStatus::Waiting);
+
+        apply_strike(&mut state, 60, 90);
+        queue.update(&state);
+        apply_strike(&mut state, 90, -1);
+        queue.update(&state);
+        assert_eq!(queue.status(id), CommandStatus::Pending);
+    }
+
+    #[test]
+    fn waiting_command_expires_after_timeout() {
+        let mut queue = CommandQueue::new();
+        let id = queue.push_when(Command::without_selection(Action::move_by(1.0, 0.0, 0.0)),
+                                 Condition::AtTick(100), Some(10));
+
+        queue.update(&state(5));
+        queue.update(&state(14));
+        assert_eq!(queue.status(id), CommandStatus::Waiting);
+
+        queue.update(&state(15));
+        assert_eq!(queue.status(id), CommandStatus::Expired);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn command_is_released_when_condition_is_met_before_timeout() {
+        let mut queue = CommandQueue::new();
+        let first = queue.push_when(Command::without_selection(Action::move_by(1.0, 0.0, 0.0)),
+                                    Condition::AtTick(10), Some(20));
+        let second = queue.push_when(Command::without_selection(Action::move_by(2.0, 0.0, 0.0)),
+                                     Condition::Any(vec![Condition::Completed(first), Condition::AtTick(100)]), None);
+
+        queue.update(&state(10));
+        assert_eq!(queue.status(first), CommandStatus::Pending);
+        assert_eq!(act_all(&mut queue).len(), 1);
+        assert_eq!(queue.status(first), CommandStatus::Done);
+
+        queue.update(&state(11));
+        assert_eq!(queue.status(second), CommandStatus::Pending);
+    }
+}
@@ -0,0 +1,157 @@
+use model::{Facility, Game, Player, VehicleType, World};
+use visibility::Visibility;
+use world_state::WorldState;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    VehicleSpawned { vehicle_id: i64, player_id: i64, kind: Option<VehicleType> },
+    VehicleDestroyed { vehicle_id: i64, player_id: i64, kind: Option<VehicleType> },
+    VehicleDamaged { vehicle_id: i64, player_id: i64, damage: i32, durability: i32 },
+    VehicleRepaired { vehicle_id: i64, player_id: i64, repaired: i32, durability: i32 },
+    FacilityOwnerChanged { facility_id: i64, previous_owner_player_id: i64, owner_player_id: i64 },
+    FacilityProductionCompleted { facility_id: i64, owner_player_id: i64, vehicle_type: Option<VehicleType> },
+    NuclearStrikeLaunched { player_id: i64, vehicle_id: i64, tick_index: i32, x: f64, y: f64 },
+    ScoreChanged { player_id: i64, previous_score: i32, score: i32 },
+}
+
+pub fn collect(state: &WorldState, world: &World, game: &Game) -> Vec<GameEvent> {
+    let mut events = Vec::new();
+    collect_vehicles(state, world, game, &mut events);
+    collect_facilities(state.facilities(), &world.facilities, &mut events);
+    collect_players(state.players(), &world.players, &mut events);
+    events
+}
+
+fn collect_vehicles(state: &WorldState, world: &World, game: &Game, events: &mut Vec<GameEvent>) {
+    let visibility = Visibility::new(state, world, game);
+
+    for vehicle in world.new_vehicles.iter() {
+        events.push(GameEvent::VehicleSpawned {
+            vehicle_id: vehicle.id,
+            player_id: vehicle.player_id,
+            kind: vehicle.kind,
+        });
+    }
+
+    for update in world.vehicle_updates.iter() {
+        let vehicle = match state.vehicle(update.id) {
+            Some(v) => v,
+            None => continue,
+        };
+
+        if update.durability <= 0 {
+            if !visibility.confirms_destruction(vehicle) {
+                continue;
+            }
+            events.push(GameEvent::VehicleDestroyed {
+                vehicle_id: vehicle.id,
+                player_id: vehicle.player_id,
+                kind: vehicle.kind,
+            });
+        } else if update.durability < vehicle.durability {
+            events.push(GameEvent::VehicleDamaged {
+                vehicle_id: vehicle.id,
+                player_id: vehicle.player_id,
+                damage: vehicle.durability - update.durability,
+                durability: update.durability,
+            });
+        } else if update.durability > vehicle.durability {
+            events.push(GameEvent::VehicleRepaired {
+                vehicle_id: vehicle.id,
+                player_id: vehicle.player_id,
+                repaired: update.durability - vehicle.durability,
+                durability: update.durability,
+            });
+        }
+    }
+}
+
+fn collect_facilities(previous: &[Facility], current: &[Facility], events: &mut Vec<GameEvent>) {
+    for facility in current.iter() {
+        let old = match previous.iter().find(|v| v.id == facility.id) {
+            Some(v) => v,
+            None => continue,
+        };
+
+        if facility.owner_player_id != old.owner_player_id {
+            events.push(GameEvent::FacilityOwnerChanged {
+                facility_id: facility.id,
+                previous_owner_player_id: old.owner_player_id,
+                owner_player_id: facility.owner_player_id,
+            });
+        } else if facility.vehicle_type.is_some() && facility.vehicle_type == old.vehicle_type
+            && facility.production_progress < old.production_progress {
+            events.push(GameEvent::FacilityProductionCompleted {
+                facility_id: facility.id,
+                owner_player_id: facility.owner_player_id,
+                vehicle_type: facility.vehicle_type,
+            });
+        }
+    }
+}
+
+fn collect_players(previous: &[Player], current: &[Player], events: &mut Vec<GameEvent>) {
+    for player in current.iter() {
+        let old = previous.iter().find(|v| v.id == player.id);
+
+        if player.next_nuclear_strike_tick_index >= 0
+            && old.is_none_or(|v| v.next_nuclear_strike_tick_index != player.next_nuclear_strike_tick_index) {
+            events.push(GameEvent::NuclearStrikeLaunched {
+                player_id: player.id,
+                vehicle_id: player.next_nuclear_strike_vehicle_id,
+                tick_index: player.next_nuclear_strike_tick_index,
+                x: player.next_nuclear_strike_x,
+                y: player.next_nuclear_strike_y,
+            });
+        }
+
+        if let Some(old) = old {
+            if player.score != old.score {
+                events.push(GameEvent::ScoreChanged {
+                    player_id: player.id,
+                    previous_score: old.score,
+                    score: player.score,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_fixtures::{game, players, update, vehicle, world};
+    use world_state::WorldState;
+    use super::{collect, GameEvent};
+
+    fn destroyed(fog_of_war_enabled: bool) -> Vec<i64> {
+        let mut game = game();
+        game.fog_of_war_enabled = fog_of_war_enabled;
+        let mut state = WorldState::new();
+        let mine = vehicle(1, 1, 100.0, 100.0);
+        let at_edge = vehicle(2, 2, 178.0, 100.0);
+        let inside = vehicle(3, 2, 120.0, 100.0);
+        state.apply(&world(0, players(), vec![mine.clone(), at_edge.clone(), inside.clone()], Vec::new()));
+
+        let next = world(1, players(), Vec::new(), vec![
+            update(&mine, 100.0, 100.0, 0, Vec::new()),
+            update(&at_edge, 178.0, 100.0, 0, Vec::new()),
+            update(&inside, 120.0, 100.0, 0, Vec::new()),
+        ]);
+        collect(&state, &next, &game).into_iter()
+            .filter_map(|v| match v {
+                GameEvent::VehicleDestroyed { vehicle_id, .. } => Some(vehicle_id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn enemy_leaving_vision_is_not_destroyed() {
+        assert_eq!(destroyed(true), vec![1, 3]);
+    }
+
+    #[test]
+    fn all_vehicles_are_destroyed_without_fog() {
+        assert_eq!(destroyed(false), vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::BTreeMap;
+use model::{Game, Vehicle, World};
+use visibility::Visibility;
+use world_state::WorldState;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RememberedVehicle {
+    pub vehicle: Vehicle,
+    pub last_seen_tick: i32,
+    pub visible: bool,
+    pub speed_x: f64,
+    pub speed_y: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PositionEstimate {
+    pub x: f64,
+    pub y: f64,
+    pub radius: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct FogMemory {
+    ttl_ticks: i32,
+    tick_index: i32,
+    enemies: BTreeMap<i64, RememberedVehicle>,
+}
+
+impl FogMemory {
+    pub fn new(ttl_ticks: i32) -> Self {
+        FogMemory { ttl_ticks, tick_index: -1, enemies: BTreeMap::new() }
+    }
+
+    pub fn apply(&mut self, state: &WorldState, world: &World, game: &Game) {
+        self.tick_index = world.tick_index;
+
+        let visibility = Visibility::new(state, world, game);
+
+        for vehicle in world.new_vehicles.iter() {
+            if vehicle.durability > 0 && !visibility.is_mine(vehicle) {
+                self.see(vehicle.clone(), world.tick_index);
+            }
+        }
+
+        for update in world.vehicle_updates.iter() {
+            let vehicle = match self.enemies.get(&update.id) {
+                Some(v) => &v.vehicle,
+                None => continue,
+            };
+            if update.durability > 0 {
+                let mut vehicle = vehicle.clone();
+                vehicle.update(update);
+                self.see(vehicle, world.tick_index);
+            } else if visibility.confirms_destruction(vehicle) {
+                self.enemies.remove(&update.id);
+            } else if let Some(remembered) = self.enemies.get_mut(&update.id) {
+                remembered.visible = false;
+            }
+        }
+
+        for remembered in self.enemies.values_mut() {
+            if !remembered.visible || visibility.is_reported(remembered.vehicle.id) {
+                continue;
+            }
+            if visibility.can_see(&remembered.vehicle) {
+                remembered.last_seen_tick = world.tick_index;
+                remembered.speed_x = 0.0;
+                remembered.speed_y = 0.0;
+            } else {
+                remembered.visible = false;
+            }
+        }
+
+        let (tick_index, ttl_ticks) = (self.tick_index, self.ttl_ticks);
+        self.enemies.retain(|_, v| v.visible || tick_index - v.last_seen_tick <= ttl_ticks);
+    }
+
+    pub fn get(&self, id: i64) -> Option<&RememberedVehicle> {
+        self.enemies.get(&id)
+    }
+
+    pub fn all(&self) -> impl Iterator<Item=&RememberedVehicle> {
+        self.enemies.values()
+    }
+
+    pub fn visible(&self) -> impl Iterator<Item=&RememberedVehicle> {
+        self.all().filter(|v| v.visible)
+    }
+
+    pub fn remembered(&self) -> impl Iterator<Item=&RememberedVehicle> {
+        self.all().filter(|v| !v.visible)
+    }
+
+    pub fn estimate(&self, id: i64, world: &World) -> Option<PositionEstimate> {
+        self.enemies.get(&id).map(|v| {
+            let ticks = (self.tick_index - v.last_seen_tick).max(0) as f64;
+            PositionEstimate {
+                x: (v.vehicle.x + v.speed_x * ticks).max(0.0).min(world.width),
+                y: (v.vehicle.y + v.speed_y * ticks).max(0.0).min(world.height),
+                radius: v.vehicle.max_speed * ticks,
+            }
+        })
+    }
+
+    fn see(&mut self, vehicle: Vehicle, tick_index: i32) {
+        match self.enemies.get_mut(&vehicle.id) {
+            Some(remembered) => {
+                let ticks = (tick_index - remembered.last_seen_tick).max(1) as f64;
+                remembered.speed_x = (vehicle.x - remembered.vehicle.x) / ticks;
+                remembered.speed_y = (vehicle.y - remembered.vehicle.y) / ticks;
+                remembered.vehicle = vehicle;
+                remembered.last_seen_tick = tick_index;
+                remembered.visible = true;
+            }
+            None => {
+                self.enemies.insert(vehicle.id, RememberedVehicle {
+                    vehicle,
+                    last_seen_tick: tick_index,
+                    visible: true,
+                    speed_x: 0.0,
+                    speed_y: 0.0,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use model::{Game, Vehicle, World};
+    use test_fixtures::{game, players, update, vehicle, world};
+    use world_state::WorldState;
+    use super::FogMemory;
+
+    struct Fixture {
+        game: Game,
+        state: WorldState,
+        memory: FogMemory,
+        mine: Vehicle,
+        at_edge: Vehicle,
+        inside: Vehicle,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let mut game = game();
+            game.fog_of_war_enabled = true;
+            let mut fixture = Fixture {
+                game,
+                state: WorldState::new(),
+                memory: FogMemory::new(10),
+                mine: vehicle(1, 1, 100.0, 100.0),
+                at_edge: vehicle(2, 2, 178.0, 100.0),
+                inside: vehicle(3, 2, 120.0, 100.0),
+            };
+            let first = world(0, players(), vec![fixture.mine.clone(), fixture.at_edge.clone(),
+                                                 fixture.inside.clone()], Vec::new());
+            fixture.apply(&first);
+            fixture
+        }
+
+        fn apply(&mut self, world: &World) {
+            self.state.apply(world);
+            self.memory.apply(&self.state, world, &self.game);
+        }
+    }
+
+    #[test]
+    fn enemy_leaving_vision_is_hidden_until_ttl() {
+        let mut fixture = Fixture::new();
+        let left = world(1, players(), Vec::new(), vec![update(&fixture.at_edge, 178.0, 100.0, 0, Vec::new())]);
+        fixture.apply(&left);
+
+        let remembered = fixture.memory.get(2).unwrap();
+        assert!(!remembered.visible);
+        assert_eq!(remembered.last_seen_tick, 0);
+        assert_eq!(fixture.memory.remembered().count(), 1);
+        assert_eq!(fixture.memory.visible().map(|v| v.vehicle.id).collect::<Vec<_>>(), vec![3]);
+
+        for tick_index in 2..11 {
+            fixture.apply(&world(tick_index, players(), Vec::new(), Vec::new()));
+        }
+        assert!(fixture.memory.get(2).is_some());
+        fixture.apply(&world(11, players(), Vec::new(), Vec::new()));
+        assert!(fixture.memory.get(2).is_none());
+        assert!(fixture.memory.get(3).unwrap().visible);
+    }
+
+    #[test]
+    fn hidden_enemy_becomes_visible_again() {
+        let mut fixture = Fixture::new();
+        fixture.apply(&world(1, players(), Vec::new(), vec![update(&fixture.at_edge, 178.0, 100.0, 0, Vec::new())]));
+        let mut back = fixture.at_edge.clone();
+        back.x = 175.0;
+        fixture.apply(&world(4, players(), vec![back], Vec::new()));
+
+        let remembered = fixture.memory.get(2).unwrap();
+        assert!(remembered.visible);
+        assert_eq!(remembered.last_seen_tick, 4);
+        assert_eq!(remembered.speed_x, -0.75);
+    }
+
+    #[test]
+    fn enemy_destroyed_inside_vision_is_forgotten() {
+        let mut fixture = Fixture::new();
+        let destroyed = world(1, players(), Vec::new(), vec![update(&fixture.inside, 120.0, 100.0, 0, Vec::new())]);
+        fixture.apply(&destroyed);
+
+        assert!(fixture.memory.get(3).is_none());
+        assert!(fixture.memory.get(2).unwrap().visible);
+        assert!(fixture.memory.get(1).is_none());
+    }
+}
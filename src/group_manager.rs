@@ -0,0 +1,237 @@
+use std::collections::{BTreeMap, BTreeSet};
+use model::{Action, Game, Rect, VehicleType, World};
+use world_state::WorldState;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GroupInfo<M> {
+    pub group: i32,
+    pub metadata: M,
+    pub count: usize,
+    pub composition: [usize; VehicleType::COUNT],
+    pub centroid: Option<(f64, f64)>,
+    pub bounds: Option<Rect>,
+    pub durability: i32,
+    pub max_durability: i32,
+    pub wiped_out: bool,
+}
+
+impl<M> GroupInfo<M> {
+    fn new(group: i32, metadata: M) -> Self {
+        GroupInfo {
+            group,
+            metadata,
+            count: 0,
+            composition: [0; VehicleType::COUNT],
+            centroid: None,
+            bounds: None,
+            durability: 0,
+            max_durability: 0,
+            wiped_out: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GroupManager<M> {
+    max_unit_group: i32,
+    groups: BTreeMap<i32, GroupInfo<M>>,
+    disbanding: BTreeSet<i32>,
+}
+
+impl<M> GroupManager<M> {
+    pub fn new(game: &Game) -> Self {
+        GroupManager { max_unit_group: game.max_unit_group, groups: BTreeMap::new(), disbanding: BTreeSet::new() }
+    }
+
+    pub fn allocate(&mut self, metadata: M) -> Option<i32> {
+        let group = (1..(self.max_unit_group + 1))
+            .find(|v| !self.groups.contains_key(v) && !self.disbanding.contains(v))?;
+        self.groups.insert(group, GroupInfo::new(group, metadata));
+        Some(group)
+    }
+
+    pub fn assign(&mut self, metadata: M) -> Option<(i32, Action)> {
+        self.allocate(metadata).map(|group| (group, Action::assign(group)))
+    }
+
+    pub fn dismiss(&self, group: i32) -> Option<Action> {
+        if self.groups.contains_key(&group) {
+            Some(Action::dismiss(group))
+        } else {
+            None
+        }
+    }
+
+    pub fn disband(&mut self, group: i32) -> Option<(M, Action)> {
+        self.free(group).map(|metadata| (metadata, Action::disband(group)))
+    }
+
+    pub fn free(&mut self, group: i32) -> Option<M> {
+        let info = self.groups.remove(&group)?;
+        self.disbanding.insert(group);
+        Some(info.metadata)
+    }
+
+    pub fn is_allocated(&self, group: i32) -> bool {
+        self.groups.contains_key(&group)
+    }
+
+    pub fn is_disbanding(&self, group: i32) -> bool {
+        self.disbanding.contains(&group)
+    }
+
+    pub fn free_count(&self) -> usize {
+        (self.max_unit_group.max(0) as usize).saturating_sub(self.groups.len() + self.disbanding.len())
+    }
+
+    pub fn get(&self, group: i32) -> Option<&GroupInfo<M>> {
+        self.groups.get(&group)
+    }
+
+    pub fn metadata_mut(&mut self, group: i32) -> Option<&mut M> {
+        self.groups.get_mut(&group).map(|v| &mut v.metadata)
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item=&GroupInfo<M>> {
+        self.groups.values()
+    }
+
+    pub fn update(&mut self, state: &WorldState, world: &World) -> Vec<i32> {
+        let mut wiped_out = Vec::new();
+
+        self.disbanding.retain(|&group| state.in_group(group).next().is_some());
+
+        for info in self.groups.values_mut() {
+            let had_vehicles = info.count > 0;
+            info.count = 0;
+            info.composition = [0; VehicleType::COUNT];
+            info.durability = 0;
+            info.max_durability = 0;
+
+            for vehicle in state.in_group(info.group) {
+                info.count += 1;
+                if let Some(kind) = vehicle.kind {
+                    info.composition[kind as usize] += 1;
+                }
+                info.durability += vehicle.durability;
+                info.max_durability += vehicle.max_durability;
+            }
+
+            let extent = state.group_extent(info.group);
+            info.centroid = extent.map(|v| (v.centroid_x, v.centroid_y));
+            info.bounds = extent.map(|v| v.bounds);
+
+            let lost_vehicles = world.vehicle_updates.iter()
+                .any(|v| v.durability <= 0 && v.groups.contains(&info.group));
+
+            if info.count > 0 {
+                info.wiped_out = false;
+            } else if had_vehicles || lost_vehicles {
+                info.wiped_out = true;
+                wiped_out.push(info.group);
+            }
+        }
+
+        wiped_out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use model::Rect;
+    use test_fixtures::{game, players, update, vehicle, world};
+    use world_state::WorldState;
+    use super::GroupManager;
+
+    #[test]
+    fn disbanded_group_is_not_reused_while_vehicles_carry_it() {
+        let mut state = WorldState::new();
+        let mut manager = GroupManager::new(&game());
+        let mut tank = vehicle(1, 1, 100.0, 100.0);
+        tank.groups = vec![1];
+        let current = world(0, players(), vec![tank.clone()], Vec::new());
+        state.apply(&current);
+
+        assert_eq!(manager.assign("a").map(|v| v.0), Some(1));
+        manager.update(&state, &current);
+        assert_eq!(manager.disband(1).map(|v| v.0), Some("a"));
+        assert!(manager.is_disbanding(1));
+        assert_eq!(manager.allocate("b"), Some(2));
+
+        let current = world(1, players(), Vec::new(), Vec::new());
+        state.apply(&current);
+        assert_eq!(manager.update(&state, &current), Vec::<i32>::new());
+        assert!(manager.is_disbanding(1));
+
+        let current = world(2, players(), Vec::new(), vec![update(&tank, 100.0, 100.0, 100, Vec::new())]);
+        state.apply(&current);
+        assert_eq!(manager.update(&state, &current), Vec::<i32>::new());
+        assert!(!manager.is_disbanding(1));
+        assert_eq!(manager.allocate("c"), Some(1));
+    }
+
+    #[test]
+    fn wiped_out_is_reset_when_group_gets_vehicles_again() {
+        let mut state = WorldState::new();
+        let mut manager = GroupManager::new(&game());
+        let mut tank = vehicle(1, 1, 100.0, 100.0);
+        tank.groups = vec![1];
+        let current = world(0, players(), vec![tank.clone()], Vec::new());
+        state.apply(&current);
+        manager.assign(());
+        manager.update(&state, &current);
+
+        let current = world(1, players(), Vec::new(), vec![update(&tank, 100.0, 100.0, 0, vec![1])]);
+        state.apply(&current);
+        assert_eq!(manager.update(&state, &current), vec![1]);
+        assert!(manager.get(1).unwrap().wiped_out);
+
+        let mut other = vehicle(2, 1, 200.0, 100.0);
+        other.groups = vec![1];
+        let current = world(2, players(), vec![other], Vec::new());
+        state.apply(&current);
+        assert_eq!(manager.update(&state, &current), Vec::<i32>::new());
+        assert!(!manager.get(1).unwrap().wiped_out);
+        assert_eq!(manager.get(1).unwrap().count, 1);
+    }
+
+    #[test]
+    fn group_killed_in_assign_tick_is_wiped_out() {
+        let mut state = WorldState::new();
+        let mut manager = GroupManager::new(&game());
+        let tank = vehicle(1, 1, 100.0, 100.0);
+        let current = world(0, players(), vec![tank.clone()], Vec::new());
+        state.apply(&current);
+
+        assert_eq!(manager.assign(()).map(|v| v.0), Some(1));
+        assert_eq!(manager.assign(()).map(|v| v.0), Some(2));
+        let current = world(1, players(), Vec::new(), vec![update(&tank, 100.0, 100.0, 0, vec![1])]);
+        state.apply(&current);
+
+        assert_eq!(manager.update(&state, &current), vec![1]);
+        assert!(manager.get(1).unwrap().wiped_out);
+        assert!(!manager.get(2).unwrap().wiped_out);
+    }
+
+    #[test]
+    fn group_stats_use_group_extent() {
+        let mut state = WorldState::new();
+        let mut manager = GroupManager::new(&game());
+        let mut first = vehicle(1, 1, 100.0, 200.0);
+        let mut second = vehicle(2, 1, 300.0, 100.0);
+        first.groups = vec![1];
+        second.groups = vec![1];
+        second.durability = 50;
+        let current = world(0, players(), vec![first, second], Vec::new());
+        state.apply(&current);
+        manager.allocate(());
+
+        assert_eq!(manager.update(&state, &current), Vec::<i32>::new());
+        let info = manager.get(1).unwrap();
+        assert_eq!(info.count, 2);
+        assert_eq!(info.durability, 150);
+        assert_eq!(info.centroid, Some((200.0, 150.0)));
+        assert_eq!(info.bounds, Some(Rect::new(100.0, 100.0, 300.0, 200.0)));
+        assert_eq!(state.group_extent(1).map(|v| v.bounds), info.bounds);
+    }
+}
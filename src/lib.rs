@@ -0,0 +1,23 @@
+extern crate core;
+
+pub mod action_budget;
+pub mod action_validator;
+pub mod args;
+pub mod capture;
+pub mod combat;
+pub mod command_queue;
+pub mod events;
+pub mod fog_memory;
+pub mod group_manager;
+pub mod model;
+pub mod nuclear_strike;
+pub mod protocol_error;
+pub mod remote_process_client;
+pub mod runner;
+pub mod spatial_index;
+pub mod strategy;
+#[cfg(test)]
+mod test_fixtures;
+pub mod velocity;
+pub mod visibility;
+pub mod world_state;
@@ -0,0 +1,60 @@
+extern crate code_wars_rust_cgdk;
+
+mod my_strategy;
+
+use std::fs::File;
+use std::io;
+use code_wars_rust_cgdk::{model, strategy};
+use code_wars_rust_cgdk::args::{Args, ArgsError, USAGE};
+use code_wars_rust_cgdk::protocol_error::{ProtocolError, ProtocolResult};
+use code_wars_rust_cgdk::remote_process_client::RemoteProcessClient;
+use code_wars_rust_cgdk::runner::Runner;
+
+fn main() {
+    use std::io::{stderr, stdout, Write};
+    use std::process::exit;
+
+    let args = match Args::from_env() {
+        Ok(v) => v,
+        Err(ArgsError::Help) => {
+            write!(&mut stdout(), "{}", USAGE).unwrap();
+            return;
+        }
+        Err(v) => {
+            writeln!(&mut stderr(), "{}\n\n{}", v, USAGE).unwrap();
+            exit(2);
+        }
+    };
+
+    let result = if let Some(ref path) = args.replay {
+        RemoteProcessClient::replay(path)
+            .map_err(ProtocolError::from)
+            .and_then(|client| run(client, &args))
+    } else if let Some(ref path) = args.capture {
+        RemoteProcessClient::connect_with_capture(&args.host[..], args.port, path)
+            .map_err(ProtocolError::from)
+            .and_then(|client| run(client, &args))
+    } else {
+        RemoteProcessClient::connect(&args.host[..], args.port)
+            .map_err(ProtocolError::from)
+            .and_then(|client| run(client, &args))
+    };
+
+    match result {
+        Ok(_) => (),
+        Err(v) => {
+            writeln!(&mut stderr(), "{}", v).unwrap();
+            exit(-1);
+        }
+    }
+}
+
+fn run<R: io::Read + Send, W: io::Write + Send>(client: RemoteProcessClient<R, W>, args: &Args) -> ProtocolResult<()> {
+    use my_strategy::MyStrategy;
+
+    let mut runner = Runner::with_config(client, args.token.clone(), args.config.clone());
+    if let Some(ref path) = args.log {
+        runner.set_log(File::create(path)?);
+    }
+    runner.run::<MyStrategy>()
+}
@@ -1,4 +1,5 @@
 use super::action_type::ActionType;
+use super::rect::Rect;
 use super::vehicle_type::VehicleType;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -41,3 +42,77 @@ impl Default for Action {
         }
     }
 }
+
+impl Action {
+    pub fn clear_and_select(rect: Rect, vehicle_type: Option<VehicleType>) -> Self {
+        Action::select_rect(ActionType::ClearAndSelect, rect, vehicle_type)
+    }
+
+    pub fn add_to_selection(rect: Rect, vehicle_type: Option<VehicleType>) -> Self {
+        Action::select_rect(ActionType::AddToSelection, rect, vehicle_type)
+    }
+
+    pub fn deselect(rect: Rect, vehicle_type: Option<VehicleType>) -> Self {
+        Action::select_rect(ActionType::Deselect, rect, vehicle_type)
+    }
+
+    pub fn select_group(group: i32) -> Self {
+        Action::with_group(ActionType::ClearAndSelect, group)
+    }
+
+    pub fn add_group_to_selection(group: i32) -> Self {
+        Action::with_group(ActionType::AddToSelection, group)
+    }
+
+    pub fn deselect_group(group: i32) -> Self {
+        Action::with_group(ActionType::Deselect, group)
+    }
+
+    pub fn assign(group: i32) -> Self {
+        Action::with_group(ActionType::Assign, group)
+    }
+
+    pub fn dismiss(group: i32) -> Self {
+        Action::with_group(ActionType::Dismiss, group)
+    }
+
+    pub fn disband(group: i32) -> Self {
+        Action::with_group(ActionType::Disband, group)
+    }
+
+    pub fn move_by(x: f64, y: f64, max_speed: f64) -> Self {
+        Action { action: Some(ActionType::Move), x, y, max_speed, ..Action::default() }
+    }
+
+    pub fn rotate(x: f64, y: f64, angle: f64, max_speed: f64, max_angular_speed: f64) -> Self {
+        Action { action: Some(ActionType::Rotate), x, y, angle, max_speed, max_angular_speed, ..Action::default() }
+    }
+
+    pub fn scale(x: f64, y: f64, factor: f64) -> Self {
+        Action { action: Some(ActionType::Scale), x, y, factor, ..Action::default() }
+    }
+
+    pub fn setup_production(facility_id: i64, vehicle_type: Option<VehicleType>) -> Self {
+        Action { action: Some(ActionType::SetupVehicleProduction), facility_id, vehicle_type, ..Action::default() }
+    }
+
+    pub fn nuclear_strike(x: f64, y: f64, vehicle_id: i64) -> Self {
+        Action { action: Some(ActionType::TacticalNuclearStrike), x, y, vehicle_id, ..Action::default() }
+    }
+
+    fn select_rect(action: ActionType, rect: Rect, vehicle_type: Option<VehicleType>) -> Self {
+        Action {
+            action: Some(action),
+            left: rect.left,
+            top: rect.top,
+            right: rect.right,
+            bottom: rect.bottom,
+            vehicle_type,
+            ..Action::default()
+        }
+    }
+
+    fn with_group(action: ActionType, group: i32) -> Self {
+        Action { action: Some(action), group, ..Action::default() }
+    }
+}
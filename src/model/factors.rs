@@ -0,0 +1,12 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Factors {
+    pub speed: f64,
+    pub vision: f64,
+    pub stealth: f64,
+}
+
+impl Default for Factors {
+    fn default() -> Self {
+        Factors { speed: 1.0, vision: 1.0, stealth: 1.0 }
+    }
+}
@@ -1,3 +1,9 @@
+use super::factors::Factors;
+use super::terrain_type::TerrainType;
+use super::vehicle_stats::VehicleStats;
+use super::vehicle_type::VehicleType;
+use super::weather_type::WeatherType;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Game {
     pub random_seed: i64,
@@ -95,3 +101,154 @@ pub struct Game {
     pub tactical_nuclear_strike_radius: f64,
     pub tactical_nuclear_strike_delay: i32,
 }
+
+impl Game {
+    pub fn terrain_factors(&self, terrain: TerrainType) -> Factors {
+        match terrain {
+            TerrainType::Plain => Factors {
+                speed: self.plain_terrain_speed_factor,
+                vision: self.plain_terrain_vision_factor,
+                stealth: self.plain_terrain_stealth_factor,
+            },
+            TerrainType::Swamp => Factors {
+                speed: self.swamp_terrain_speed_factor,
+                vision: self.swamp_terrain_vision_factor,
+                stealth: self.swamp_terrain_stealth_factor,
+            },
+            TerrainType::Forest => Factors {
+                speed: self.forest_terrain_speed_factor,
+                vision: self.forest_terrain_vision_factor,
+                stealth: self.forest_terrain_stealth_factor,
+            },
+        }
+    }
+
+    pub fn weather_factors(&self, weather: WeatherType) -> Factors {
+        match weather {
+            WeatherType::Clear => Factors {
+                speed: self.clear_weather_speed_factor,
+                vision: self.clear_weather_vision_factor,
+                stealth: self.clear_weather_stealth_factor,
+            },
+            WeatherType::Cloud => Factors {
+                speed: self.cloud_weather_speed_factor,
+                vision: self.cloud_weather_vision_factor,
+                stealth: self.cloud_weather_stealth_factor,
+            },
+            WeatherType::Rain => Factors {
+                speed: self.rain_weather_speed_factor,
+                vision: self.rain_weather_vision_factor,
+                stealth: self.rain_weather_stealth_factor,
+            },
+        }
+    }
+
+    pub fn stats(&self, kind: VehicleType) -> VehicleStats {
+        match kind {
+            VehicleType::Arrv => VehicleStats {
+                aerial: false,
+                durability: self.arrv_durability,
+                speed: self.arrv_speed,
+                vision_range: self.arrv_vision_range,
+                ground_attack_range: 0.0,
+                aerial_attack_range: 0.0,
+                ground_damage: 0,
+                aerial_damage: 0,
+                ground_defence: self.arrv_ground_defence,
+                aerial_defence: self.arrv_aerial_defence,
+                attack_cooldown_ticks: 0,
+                production_cost: self.arrv_production_cost,
+                repair_range: self.arrv_repair_range,
+                repair_speed: self.arrv_repair_speed,
+            },
+            VehicleType::Fighter => VehicleStats {
+                aerial: true,
+                durability: self.fighter_durability,
+                speed: self.fighter_speed,
+                vision_range: self.fighter_vision_range,
+                ground_attack_range: self.fighter_ground_attack_range,
+                aerial_attack_range: self.fighter_aerial_attack_range,
+                ground_damage: self.fighter_ground_damage,
+                aerial_damage: self.fighter_aerial_damage,
+                ground_defence: self.fighter_ground_defence,
+                aerial_defence: self.fighter_aerial_defence,
+                attack_cooldown_ticks: self.fighter_attack_cooldown_ticks,
+                production_cost: self.fighter_production_cost,
+                repair_range: 0.0,
+                repair_speed: 0.0,
+            },
+            VehicleType::Helicopter => VehicleStats {
+                aerial: true,
+                durability: self.helicopter_durability,
+                speed: self.helicopter_speed,
+                vision_range: self.helicopter_vision_range,
+                ground_attack_range: self.helicopter_ground_attack_range,
+                aerial_attack_range: self.helicopter_aerial_attack_range,
+                ground_damage: self.helicopter_ground_damage,
+                aerial_damage: self.helicopter_aerial_damage,
+                ground_defence: self.helicopter_ground_defence,
+                aerial_defence: self.helicopter_aerial_defence,
+                attack_cooldown_ticks: self.helicopter_attack_cooldown_ticks,
+                production_cost: self.helicopter_production_cost,
+                repair_range: 0.0,
+                repair_speed: 0.0,
+            },
+            VehicleType::Ifv => VehicleStats {
+                aerial: false,
+                durability: self.ifv_durability,
+                speed: self.ifv_speed,
+                vision_range: self.ifv_vision_range,
+                ground_attack_range: self.ifv_ground_attack_range,
+                aerial_attack_range: self.ifv_aerial_attack_range,
+                ground_damage: self.ifv_ground_damage,
+                aerial_damage: self.ifv_aerial_damage,
+                ground_defence: self.ifv_ground_defence,
+                aerial_defence: self.ifv_aerial_defence,
+                attack_cooldown_ticks: self.ifv_attack_cooldown_ticks,
+                production_cost: self.ifv_production_cost,
+                repair_range: 0.0,
+                repair_speed: 0.0,
+            },
+            VehicleType::Tank => VehicleStats {
+                aerial: false,
+                durability: self.tank_durability,
+                speed: self.tank_speed,
+                vision_range: self.tank_vision_range,
+                ground_attack_range: self.tank_ground_attack_range,
+                aerial_attack_range: self.tank_aerial_attack_range,
+                ground_damage: self.tank_ground_damage,
+                aerial_damage: self.tank_aerial_damage,
+                ground_defence: self.tank_ground_defence,
+                aerial_defence: self.tank_aerial_defence,
+                attack_cooldown_ticks: self.tank_attack_cooldown_ticks,
+                production_cost: self.tank_production_cost,
+                repair_range: 0.0,
+                repair_speed: 0.0,
+            },
+        }
+    }
+
+    pub fn all_stats(&self) -> [VehicleStats; VehicleType::COUNT] {
+        [
+            self.stats(VehicleType::Arrv),
+            self.stats(VehicleType::Fighter),
+            self.stats(VehicleType::Helicopter),
+            self.stats(VehicleType::Ifv),
+            self.stats(VehicleType::Tank),
+        ]
+    }
+
+    pub fn cell_x_y(&self, x: f64, y: f64) -> (usize, usize) {
+        (
+            cell_index(x, self.world_width, self.terrain_weather_map_column_count),
+            cell_index(y, self.world_height, self.terrain_weather_map_row_count),
+        )
+    }
+}
+
+fn cell_index(value: f64, size: f64, count: i32) -> usize {
+    if count <= 0 || size <= 0.0 || value.is_nan() || value <= 0.0 {
+        return 0;
+    }
+    ((value / (size / count as f64)) as usize).min(count as usize - 1)
+}
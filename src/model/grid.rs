@@ -0,0 +1,79 @@
+use std::ops::Index;
+use std::sync::Arc;
+
+#[derive(Clone, Debug)]
+pub struct Grid<T> {
+    column_count: usize,
+    row_count: usize,
+    cells: Arc<[T]>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_columns(columns: &[Vec<T>]) -> Option<Self> where T: Clone {
+        let row_count = columns.first().map_or(0, |v| v.len());
+
+        if columns.iter().any(|v| v.len() != row_count) {
+            return None;
+        }
+
+        let mut cells = Vec::with_capacity(columns.len() * row_count);
+        for y in 0..row_count {
+            for column in columns {
+                cells.push(column[y].clone());
+            }
+        }
+
+        Some(Grid { column_count: columns.len(), row_count, cells: Arc::from(cells) })
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.column_count
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.column_count && y < self.row_count {
+            Some(&self.cells[y * self.column_count + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn shares_cells_with(&self, other: &Grid<T>) -> bool {
+        Arc::ptr_eq(&self.cells, &other.cells)
+    }
+}
+
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Grid { column_count: 0, row_count: 0, cells: Arc::from(Vec::new()) }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(x < self.column_count && y < self.row_count,
+                "Grid index out of bounds: ({}, {}) for {}x{}", x, y, self.column_count, self.row_count);
+        &self.cells[y * self.column_count + x]
+    }
+}
+
+impl<T: PartialEq> PartialEq for Grid<T> {
+    fn eq(&self, other: &Grid<T>) -> bool {
+        self.column_count == other.column_count && self.row_count == other.row_count
+            && (self.shares_cells_with(other) || self.cells == other.cells)
+    }
+}
@@ -6,12 +6,16 @@ mod unit;
 mod action;
 mod action_type;
 mod facility;
+mod factors;
 mod facility_type;
 mod game;
+mod grid;
 mod player;
 mod player_context;
+mod rect;
 mod terrain_type;
 mod vehicle;
+mod vehicle_stats;
 mod vehicle_type;
 mod vehicle_update;
 mod weather_type;
@@ -21,13 +25,17 @@ pub use self::action::Action;
 pub use self::action_type::ActionType;
 pub use self::circular_unit::CircularUnit;
 pub use self::facility::Facility;
+pub use self::factors::Factors;
 pub use self::facility_type::FacilityType;
 pub use self::game::Game;
+pub use self::grid::Grid;
 pub use self::player::Player;
 pub use self::player_context::PlayerContext;
+pub use self::rect::Rect;
 pub use self::terrain_type::TerrainType;
 pub use self::unit::Unit;
 pub use self::vehicle::Vehicle;
+pub use self::vehicle_stats::VehicleStats;
 pub use self::vehicle_type::VehicleType;
 pub use self::vehicle_update::VehicleUpdate;
 pub use self::weather_type::WeatherType;
@@ -0,0 +1,29 @@
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rect {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl Rect {
+    pub fn new(left: f64, top: f64, right: f64, bottom: f64) -> Self {
+        Rect { left, top, right, bottom }
+    }
+
+    pub fn around(x: f64, y: f64, radius: f64) -> Self {
+        Rect::new(x - radius, y - radius, x + radius, y + radius)
+    }
+
+    pub fn width(&self) -> f64 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f64 {
+        self.bottom - self.top
+    }
+
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        self.left <= x && x <= self.right && self.top <= y && y <= self.bottom
+    }
+}
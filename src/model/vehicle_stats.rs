@@ -0,0 +1,31 @@
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VehicleStats {
+    pub aerial: bool,
+    pub durability: i32,
+    pub speed: f64,
+    pub vision_range: f64,
+    pub ground_attack_range: f64,
+    pub aerial_attack_range: f64,
+    pub ground_damage: i32,
+    pub aerial_damage: i32,
+    pub ground_defence: i32,
+    pub aerial_defence: i32,
+    pub attack_cooldown_ticks: i32,
+    pub production_cost: i32,
+    pub repair_range: f64,
+    pub repair_speed: f64,
+}
+
+impl VehicleStats {
+    pub fn attack_range(&self, aerial_target: bool) -> f64 {
+        if aerial_target { self.aerial_attack_range } else { self.ground_attack_range }
+    }
+
+    pub fn damage(&self, aerial_target: bool) -> i32 {
+        if aerial_target { self.aerial_damage } else { self.ground_damage }
+    }
+
+    pub fn defence(&self, aerial_attacker: bool) -> i32 {
+        if aerial_attacker { self.aerial_defence } else { self.ground_defence }
+    }
+}
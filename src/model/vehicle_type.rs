@@ -10,4 +10,19 @@ pub enum VehicleType {
 #[allow(dead_code)]
 impl VehicleType {
     pub const COUNT: usize = 5;
+
+    pub const ALL: [VehicleType; VehicleType::COUNT] = [
+        VehicleType::Arrv,
+        VehicleType::Fighter,
+        VehicleType::Helicopter,
+        VehicleType::Ifv,
+        VehicleType::Tank,
+    ];
+
+    pub fn is_aerial(self) -> bool {
+        match self {
+            VehicleType::Fighter | VehicleType::Helicopter => true,
+            VehicleType::Arrv | VehicleType::Ifv | VehicleType::Tank => false,
+        }
+    }
 }
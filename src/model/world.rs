@@ -1,4 +1,8 @@
+use std::sync::Arc;
 use super::facility::Facility;
+use super::factors::Factors;
+use super::game::Game;
+use super::grid::Grid;
 use super::player::Player;
 use super::terrain_type::TerrainType;
 use super::vehicle::Vehicle;
@@ -11,12 +15,12 @@ pub struct World {
     pub tick_count: i32,
     pub width: f64,
     pub height: f64,
-    pub players: Vec<Player>,
+    pub players: Arc<[Player]>,
     pub new_vehicles: Vec<Vehicle>,
     pub vehicle_updates: Vec<VehicleUpdate>,
-    pub terrain_by_cell_x_y: Vec<Vec<TerrainType>>,
-    pub weather_by_cell_x_y: Vec<Vec<WeatherType>>,
-    pub facilities: Vec<Facility>,
+    pub terrain_by_cell_x_y: Grid<TerrainType>,
+    pub weather_by_cell_x_y: Grid<WeatherType>,
+    pub facilities: Arc<[Facility]>,
 }
 
 #[allow(dead_code)]
@@ -28,4 +32,26 @@ impl World {
     pub fn get_opponent_player(&self) -> Option<&Player> {
         self.players.iter().find(|v| !v.me)
     }
+
+    pub fn terrain_at(&self, game: &Game, x: f64, y: f64) -> Option<TerrainType> {
+        let (cell_x, cell_y) = game.cell_x_y(x, y);
+        self.terrain_by_cell_x_y.get(cell_x, cell_y).cloned()
+    }
+
+    pub fn weather_at(&self, game: &Game, x: f64, y: f64) -> Option<WeatherType> {
+        let (cell_x, cell_y) = game.cell_x_y(x, y);
+        self.weather_by_cell_x_y.get(cell_x, cell_y).cloned()
+    }
+
+    pub fn factors_at(&self, game: &Game, x: f64, y: f64, aerial: bool) -> Factors {
+        if aerial {
+            self.weather_at(game, x, y).map_or_else(Factors::default, |v| game.weather_factors(v))
+        } else {
+            self.terrain_at(game, x, y).map_or_else(Factors::default, |v| game.terrain_factors(v))
+        }
+    }
+
+    pub fn vehicle_factors(&self, game: &Game, vehicle: &Vehicle) -> Factors {
+        self.factors_at(game, vehicle.x, vehicle.y, vehicle.aerial)
+    }
 }
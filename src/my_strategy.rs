@@ -1,22 +1,18 @@
-use model::{ActionType, Game, Action, Player, World};
+use model::{Game, Action, Player, Rect, World};
 use strategy::Strategy;
 
 #[derive(Default)]
 pub struct MyStrategy;
 
 impl Strategy for MyStrategy {
-    fn act(&mut self, me: &Player, world: &World, game: &Game, action: &mut Action) {
+    fn act(&mut self, _me: &Player, world: &World, _game: &Game, action: &mut Action) {
         if world.tick_index == 0 {
-            action.action = Some(ActionType::ClearAndSelect);
-            action.right = world.width;
-            action.bottom = world.height;
+            *action = Action::clear_and_select(Rect::new(0.0, 0.0, world.width, world.height), None);
             return;
         }
 
         if world.tick_index == 1 {
-            action.action = Some(ActionType::Move);
-            action.x = world.width / 2.0;
-            action.y = world.height / 2.0;
+            *action = Action::move_by(world.width / 2.0, world.height / 2.0, 0.0);
         }
     }
 }
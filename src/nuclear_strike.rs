@@ -0,0 +1,80 @@
+use model::{Action, Game, Rect, Unit};
+use world_state::WorldState;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IncomingStrike {
+    pub player_id: i64,
+    pub vehicle_id: i64,
+    pub x: f64,
+    pub y: f64,
+    pub tick_index: i32,
+    pub ticks_remaining: i32,
+    pub threatened: Vec<ThreatenedVehicle>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThreatenedVehicle {
+    pub vehicle_id: i64,
+    pub distance: f64,
+    pub expected_damage: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduledAction {
+    pub tick_index: i32,
+    pub action: Action,
+}
+
+impl IncomingStrike {
+    pub fn expected_damage(&self) -> f64 {
+        self.threatened.iter().map(|v| v.expected_damage).sum()
+    }
+
+    pub fn evasion(&self, game: &Game, current_tick_index: i32, scale_factor: f64) -> Vec<ScheduledAction> {
+        let area = Rect::around(self.x, self.y, game.tactical_nuclear_strike_radius);
+        let gather_area = Rect::around(self.x, self.y, game.tactical_nuclear_strike_radius * scale_factor);
+
+        vec![
+            ScheduledAction { tick_index: current_tick_index, action: Action::clear_and_select(area, None) },
+            ScheduledAction { tick_index: current_tick_index + 1, action: Action::scale(self.x, self.y, scale_factor) },
+            ScheduledAction { tick_index: self.tick_index, action: Action::clear_and_select(gather_area, None) },
+            ScheduledAction { tick_index: self.tick_index + 1, action: Action::scale(self.x, self.y, 1.0 / scale_factor) },
+        ]
+    }
+}
+
+pub fn strike_damage(game: &Game, distance: f64) -> f64 {
+    if distance >= game.tactical_nuclear_strike_radius {
+        0.0
+    } else {
+        game.max_tactical_nuclear_strike_damage * (1.0 - distance / game.tactical_nuclear_strike_radius)
+    }
+}
+
+pub fn incoming_strike(state: &WorldState, game: &Game) -> Option<IncomingStrike> {
+    let opponent = state.opponent_player()?;
+    if opponent.next_nuclear_strike_tick_index < 0 {
+        return None;
+    }
+
+    let (x, y) = (opponent.next_nuclear_strike_x, opponent.next_nuclear_strike_y);
+    let threatened = state.mine()
+        .map(|v| (v, v.distance_to(x, y)))
+        .filter(|&(_, distance)| distance < game.tactical_nuclear_strike_radius)
+        .map(|(vehicle, distance)| ThreatenedVehicle {
+            vehicle_id: vehicle.id,
+            distance,
+            expected_damage: strike_damage(game, distance).min(vehicle.durability as f64),
+        })
+        .collect();
+
+    Some(IncomingStrike {
+        player_id: opponent.id,
+        vehicle_id: opponent.next_nuclear_strike_vehicle_id,
+        x,
+        y,
+        tick_index: opponent.next_nuclear_strike_tick_index,
+        ticks_remaining: opponent.next_nuclear_strike_tick_index - state.tick_index(),
+        threatened,
+    })
+}
@@ -0,0 +1,69 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::string::FromUtf8Error;
+use remote_process_client::Message;
+
+pub type ProtocolResult<T> = Result<T, ProtocolError>;
+
+#[derive(Debug)]
+pub enum ProtocolError {
+    Io(io::Error),
+    TruncatedStream { path: &'static str },
+    InvalidMessageId { id: i8 },
+    UnexpectedMessage { expected: &'static str, received: Box<Message> },
+    InvalidEnumValue { path: &'static str, value: i8 },
+    NegativeLength { path: &'static str, len: i32 },
+    NullValue { path: &'static str },
+    NonRectangularGrid { path: &'static str },
+    InvalidString { path: &'static str, error: FromUtf8Error },
+    UnknownPlayerId { path: &'static str, id: i64 },
+    UnknownFacilityId { path: &'static str, id: i64 },
+}
+
+impl ProtocolError {
+    pub fn truncated_at(self, path: &'static str) -> Self {
+        match self {
+            ProtocolError::Io(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                ProtocolError::TruncatedStream { path }
+            }
+            v => v,
+        }
+    }
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProtocolError::Io(ref e) => write!(f, "io error: {}", e),
+            ProtocolError::TruncatedStream { path } => write!(f, "{}: stream is truncated", path),
+            ProtocolError::InvalidMessageId { id } => write!(f, "invalid message id: {}", id),
+            ProtocolError::UnexpectedMessage { expected, ref received } => {
+                write!(f, "expected {}, but received message with id {}", expected, received.get_id())
+            }
+            ProtocolError::InvalidEnumValue { path, value } => write!(f, "{}: invalid enum value: {}", path, value),
+            ProtocolError::NegativeLength { path, len } => write!(f, "{}: negative length: {}", path, len),
+            ProtocolError::NullValue { path } => write!(f, "{}: unexpected null value", path),
+            ProtocolError::NonRectangularGrid { path } => write!(f, "{}: grid columns have different lengths", path),
+            ProtocolError::InvalidString { path, ref error } => write!(f, "{}: invalid string: {}", path, error),
+            ProtocolError::UnknownPlayerId { path, id } => write!(f, "{}: unknown cached player id: {}", path, id),
+            ProtocolError::UnknownFacilityId { path, id } => write!(f, "{}: unknown cached facility id: {}", path, id),
+        }
+    }
+}
+
+impl Error for ProtocolError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ProtocolError::Io(ref e) => Some(e),
+            ProtocolError::InvalidString { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ProtocolError {
+    fn from(value: io::Error) -> Self {
+        ProtocolError::Io(value)
+    }
+}
@@ -1,16 +1,19 @@
 use std::collections::HashMap;
-use std::io::{BufReader, BufWriter, Error, ErrorKind};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
 use std::io;
 use std::net::TcpStream;
+use std::path::Path;
+use std::sync::Arc;
 use core::fmt::Debug;
 use core::hash::Hash;
-use core::mem::transmute;
 use core::ptr::copy_nonoverlapping;
 use model::{
     ActionType,
     Facility,
     FacilityType,
     Game,
+    Grid,
     Action,
     Player,
     PlayerContext,
@@ -21,28 +24,44 @@ use model::{
     WeatherType,
     World,
 };
+use capture::{Capture, CaptureReader, CaptureWriter, Replay};
+use protocol_error::{ProtocolError, ProtocolResult};
 
 const PROTOCOL_VERSION: i32 = 3;
 
-pub struct RemoteProcessClient {
-    cache: Cache,
-    reader: BufReader<TcpStream>,
-    writer: BufWriter<TcpStream>,
+const MAX_PREALLOCATED_LEN: usize = 1024;
+
+const MESSAGE_NAMES: [&str; 8] = [
+    "Message::UnknownMessage",
+    "Message::GameOver",
+    "Message::AuthenticationToken",
+    "Message::TeamSize",
+    "Message::ProtocolVersion",
+    "Message::GameContext",
+    "Message::PlayerContext",
+    "Message::ActionMessage",
+];
+
+pub struct RemoteProcessClient<R = TcpStream, W: io::Write = TcpStream> {
+    read_cache: Cache,
+    write_cache: Cache,
+    reader: BufReader<R>,
+    writer: BufWriter<W>,
 }
 
 #[derive(Default)]
 struct Cache {
-    pub facilities: Vec<Facility>,
-    pub players: Vec<Player>,
+    pub facilities: Arc<[Facility]>,
+    pub players: Arc<[Player]>,
     pub facilities_by_id: HashMap<i64, Facility>,
     pub players_by_id: HashMap<i64, Player>,
-    pub terrain_by_cell_x_y: Vec<Vec<TerrainType>>,
-    pub weather_by_cell_x_y: Vec<Vec<WeatherType>>,
+    pub terrain_by_cell_x_y: Grid<TerrainType>,
+    pub weather_by_cell_x_y: Grid<WeatherType>,
 }
 
-#[allow(dead_code)]
+#[allow(clippy::enum_variant_names, clippy::large_enum_variant)]
 #[derive(Debug, PartialEq)]
-enum Message {
+pub enum Message {
     UnknownMessage,
     GameOver,
     AuthenticationToken(String),
@@ -54,27 +73,27 @@ enum Message {
 }
 
 macro_rules! read_enum_impl {
-    ($function:ident, $name:ident, $error_format:tt, $($variant:ident),*) => {
-        fn $function(&mut self) -> io::Result<$name> {
-            let value = self.read_i8()?;
+    ($function:ident, $name:ident, $($variant:ident),*) => {
+        fn $function(&mut self, path: &'static str) -> ProtocolResult<$name> {
+            let value = self.read_i8(path)?;
 
             if value < 0 {
-                return Err(Error::new(ErrorKind::Other, format!($error_format, value)))
+                return Err(ProtocolError::InvalidEnumValue { path, value })
             }
 
             $(if value == $name::$variant as i8 {
                 Ok($name::$variant)
             } else)* {
-                Err(Error::new(ErrorKind::Other, format!($error_format, value)))
+                Err(ProtocolError::InvalidEnumValue { path, value })
             }
         }
     };
 }
 
 macro_rules! read_option_enum_impl {
-    ($function:ident, $name:ident, $error_format:tt, $($variant:ident),*) => {
-        fn $function(&mut self) -> io::Result<Option<$name>> {
-            let value = self.read_i8()?;
+    ($function:ident, $name:ident, $($variant:ident),*) => {
+        fn $function(&mut self, path: &'static str) -> ProtocolResult<Option<$name>> {
+            let value = self.read_i8(path)?;
 
             if value < 0 {
                 return Ok(None)
@@ -83,22 +102,50 @@ macro_rules! read_option_enum_impl {
             $(if value == $name::$variant as i8 {
                 Ok(Some($name::$variant))
             } else)* {
-                Err(Error::new(ErrorKind::Other, format!($error_format, value)))
+                Err(ProtocolError::InvalidEnumValue { path, value })
             }
         }
     };
 }
 
 impl RemoteProcessClient {
-    pub fn connect<'r>(host: &'r str, port: u16) -> io::Result<(Self)> {
+    pub fn connect(host: &str, port: u16) -> io::Result<Self> {
         let stream = TcpStream::connect((host, port))?;
         stream.set_nodelay(true)?;
-        let result = RemoteProcessClient {
-            cache: Cache::default(),
-            reader: BufReader::new(stream.try_clone()?),
-            writer: BufWriter::new(stream),
-        };
-        Ok(result)
+        Ok(RemoteProcessClient::new(stream.try_clone()?, stream))
+    }
+}
+
+pub type CapturingRemoteProcessClient = RemoteProcessClient<
+    CaptureReader<TcpStream, BufWriter<File>>,
+    CaptureWriter<TcpStream, BufWriter<File>>,
+>;
+
+impl CapturingRemoteProcessClient {
+    pub fn connect_with_capture<P: AsRef<Path>>(host: &str, port: u16, capture_path: P) -> io::Result<Self> {
+        let stream = TcpStream::connect((host, port))?;
+        stream.set_nodelay(true)?;
+        let capture = Capture::create(capture_path)?;
+        Ok(RemoteProcessClient::new(capture.reader(stream.try_clone()?), capture.writer(stream)))
+    }
+}
+
+pub type ReplayRemoteProcessClient = RemoteProcessClient<Replay<BufReader<File>>, io::Sink>;
+
+impl ReplayRemoteProcessClient {
+    pub fn replay<P: AsRef<Path>>(capture_path: P) -> io::Result<Self> {
+        Ok(RemoteProcessClient::new(Replay::open(capture_path)?, io::sink()))
+    }
+}
+
+impl<R: io::Read, W: io::Write> RemoteProcessClient<R, W> {
+    pub fn new(reader: R, writer: W) -> Self {
+        RemoteProcessClient {
+            read_cache: Cache::default(),
+            write_cache: Cache::default(),
+            reader: BufReader::new(reader),
+            writer: BufWriter::new(writer),
+        }
     }
 
     pub fn write_authentication_token_message(&mut self, token: String) -> io::Result<()> {
@@ -109,31 +156,34 @@ impl RemoteProcessClient {
         self.write_message(&Message::ProtocolVersion(PROTOCOL_VERSION))
     }
 
-    pub fn read_team_size_message(&mut self) -> io::Result<i32> {
+    pub fn read_team_size_message(&mut self) -> ProtocolResult<i32> {
         match self.read_message()? {
             Message::TeamSize(v) => Ok(v),
-            v => Err(Error::new(ErrorKind::Other,
-                                format!("Expected Message::TeamSize, but received: {:?}", v))),
+            v => Err(ProtocolError::UnexpectedMessage {
+                expected: "Message::TeamSize",
+                received: Box::new(v),
+            }),
         }
     }
 
-    pub fn read_game_message(&mut self) -> io::Result<Game> {
+    pub fn read_game_message(&mut self) -> ProtocolResult<Game> {
         match self.read_message()? {
             Message::GameContext(v) => Ok(v),
-            v => Err(Error::new(ErrorKind::Other,
-                                format!("Expected Message::GameContext, but received: {:?}", v))),
+            v => Err(ProtocolError::UnexpectedMessage {
+                expected: "Message::GameContext",
+                received: Box::new(v),
+            }),
         }
     }
 
-    pub fn read_player_context_message(&mut self) -> io::Result<Option<PlayerContext>> {
+    pub fn read_player_context_message(&mut self) -> ProtocolResult<Option<PlayerContext>> {
         match self.read_message()? {
             Message::GameOver => Ok(None),
             Message::PlayerContext(v) => Ok(Some(v)),
-            v => return Err(Error::new(ErrorKind::Other,
-                                       format!("Expected Message::GameOver, \
-                                            Message::PlayerContext or \
-                                            Message::PlayerContextWithoutTrees, but \
-                                            received: {:?}", v)))
+            v => Err(ProtocolError::UnexpectedMessage {
+                expected: "Message::GameOver or Message::PlayerContext",
+                received: Box::new(v),
+            }),
         }
     }
 
@@ -141,201 +191,201 @@ impl RemoteProcessClient {
         self.write_message(&Message::ActionMessage(action))
     }
 
-    fn read_message(&mut self) -> io::Result<Message> {
-        use std::io::{Error, ErrorKind};
-        match self.read_i8()? {
-            0 => unimplemented!(),
+    pub fn read_message(&mut self) -> ProtocolResult<Message> {
+        let id = self.read_i8("Message")?;
+
+        if id < 0 || id as usize >= MESSAGE_NAMES.len() {
+            return Err(ProtocolError::InvalidMessageId { id });
+        }
+
+        self.read_message_content(id)
+    }
+
+    fn read_message_content(&mut self, id: i8) -> ProtocolResult<Message> {
+        match id {
+            0 => Ok(Message::UnknownMessage),
             1 => Ok(Message::GameOver),
-            2 => unimplemented!(),
-            3 => Ok(Message::TeamSize(self.read_i32()?)),
-            4 => unimplemented!(),
+            2 => Ok(Message::AuthenticationToken(self.read_string("AuthenticationToken")?)),
+            3 => Ok(Message::TeamSize(self.read_i32(MESSAGE_NAMES[3])?)),
+            4 => Ok(Message::ProtocolVersion(self.read_i32(MESSAGE_NAMES[4])?)),
             5 => Ok(Message::GameContext(self.read_game()?)),
             6 => Ok(Message::PlayerContext(self.read_player_context()?)),
-            7 => unimplemented!(),
-            v => Err(Error::new(ErrorKind::Other,
-                                format!("RemoteProcessClient::read_message error: invalid message id: {}", v)))
+            7 => Ok(Message::ActionMessage(self.read_action()?)),
+            v => Err(ProtocolError::InvalidMessageId { id: v }),
         }
     }
 
-    fn read_game(&mut self) -> io::Result<Game> {
-        use std::io::{Error, ErrorKind};
-
-        if !self.read_bool()? {
-            return Err(Error::new(ErrorKind::Other,
-                                  "RemoteProcessClient::read_game error: value is false"));
+    fn read_game(&mut self) -> ProtocolResult<Game> {
+        if !self.read_bool("Game")? {
+            return Err(ProtocolError::NullValue { path: "Game" });
         }
 
         let result = Game {
-            random_seed: self.read_i64()?,
-            tick_count: self.read_i32()?,
-            world_width: self.read_f64()?,
-            world_height: self.read_f64()?,
-            fog_of_war_enabled: self.read_bool()?,
-            victory_score: self.read_i32()?,
-            facility_capture_score: self.read_i32()?,
-            vehicle_elimination_score: self.read_i32()?,
-            action_detection_interval: self.read_i32()?,
-            base_action_count: self.read_i32()?,
-            additional_action_count_per_control_center: self.read_i32()?,
-            max_unit_group: self.read_i32()?,
-            terrain_weather_map_column_count: self.read_i32()?,
-            terrain_weather_map_row_count: self.read_i32()?,
-            plain_terrain_vision_factor: self.read_f64()?,
-            plain_terrain_stealth_factor: self.read_f64()?,
-            plain_terrain_speed_factor: self.read_f64()?,
-            swamp_terrain_vision_factor: self.read_f64()?,
-            swamp_terrain_stealth_factor: self.read_f64()?,
-            swamp_terrain_speed_factor: self.read_f64()?,
-            forest_terrain_vision_factor: self.read_f64()?,
-            forest_terrain_stealth_factor: self.read_f64()?,
-            forest_terrain_speed_factor: self.read_f64()?,
-            clear_weather_vision_factor: self.read_f64()?,
-            clear_weather_stealth_factor: self.read_f64()?,
-            clear_weather_speed_factor: self.read_f64()?,
-            cloud_weather_vision_factor: self.read_f64()?,
-            cloud_weather_stealth_factor: self.read_f64()?,
-            cloud_weather_speed_factor: self.read_f64()?,
-            rain_weather_vision_factor: self.read_f64()?,
-            rain_weather_stealth_factor: self.read_f64()?,
-            rain_weather_speed_factor: self.read_f64()?,
-            vehicle_radius: self.read_f64()?,
-            tank_durability: self.read_i32()?,
-            tank_speed: self.read_f64()?,
-            tank_vision_range: self.read_f64()?,
-            tank_ground_attack_range: self.read_f64()?,
-            tank_aerial_attack_range: self.read_f64()?,
-            tank_ground_damage: self.read_i32()?,
-            tank_aerial_damage: self.read_i32()?,
-            tank_ground_defence: self.read_i32()?,
-            tank_aerial_defence: self.read_i32()?,
-            tank_attack_cooldown_ticks: self.read_i32()?,
-            tank_production_cost: self.read_i32()?,
-            ifv_durability: self.read_i32()?,
-            ifv_speed: self.read_f64()?,
-            ifv_vision_range: self.read_f64()?,
-            ifv_ground_attack_range: self.read_f64()?,
-            ifv_aerial_attack_range: self.read_f64()?,
-            ifv_ground_damage: self.read_i32()?,
-            ifv_aerial_damage: self.read_i32()?,
-            ifv_ground_defence: self.read_i32()?,
-            ifv_aerial_defence: self.read_i32()?,
-            ifv_attack_cooldown_ticks: self.read_i32()?,
-            ifv_production_cost: self.read_i32()?,
-            arrv_durability: self.read_i32()?,
-            arrv_speed: self.read_f64()?,
-            arrv_vision_range: self.read_f64()?,
-            arrv_ground_defence: self.read_i32()?,
-            arrv_aerial_defence: self.read_i32()?,
-            arrv_production_cost: self.read_i32()?,
-            arrv_repair_range: self.read_f64()?,
-            arrv_repair_speed: self.read_f64()?,
-            helicopter_durability: self.read_i32()?,
-            helicopter_speed: self.read_f64()?,
-            helicopter_vision_range: self.read_f64()?,
-            helicopter_ground_attack_range: self.read_f64()?,
-            helicopter_aerial_attack_range: self.read_f64()?,
-            helicopter_ground_damage: self.read_i32()?,
-            helicopter_aerial_damage: self.read_i32()?,
-            helicopter_ground_defence: self.read_i32()?,
-            helicopter_aerial_defence: self.read_i32()?,
-            helicopter_attack_cooldown_ticks: self.read_i32()?,
-            helicopter_production_cost: self.read_i32()?,
-            fighter_durability: self.read_i32()?,
-            fighter_speed: self.read_f64()?,
-            fighter_vision_range: self.read_f64()?,
-            fighter_ground_attack_range: self.read_f64()?,
-            fighter_aerial_attack_range: self.read_f64()?,
-            fighter_ground_damage: self.read_i32()?,
-            fighter_aerial_damage: self.read_i32()?,
-            fighter_ground_defence: self.read_i32()?,
-            fighter_aerial_defence: self.read_i32()?,
-            fighter_attack_cooldown_ticks: self.read_i32()?,
-            fighter_production_cost: self.read_i32()?,
-            max_facility_capture_points: self.read_f64()?,
-            facility_capture_points_per_vehicle_per_tick: self.read_f64()?,
-            facility_width: self.read_f64()?,
-            facility_height: self.read_f64()?,
-            base_tactical_nuclear_strike_cooldown: self.read_i32()?,
-            tactical_nuclear_strike_cooldown_decrease_per_control_center: self.read_i32()?,
-            max_tactical_nuclear_strike_damage: self.read_f64()?,
-            tactical_nuclear_strike_radius: self.read_f64()?,
-            tactical_nuclear_strike_delay: self.read_i32()?,
+            random_seed: self.read_i64("Game::random_seed")?,
+            tick_count: self.read_i32("Game::tick_count")?,
+            world_width: self.read_f64("Game::world_width")?,
+            world_height: self.read_f64("Game::world_height")?,
+            fog_of_war_enabled: self.read_bool("Game::fog_of_war_enabled")?,
+            victory_score: self.read_i32("Game::victory_score")?,
+            facility_capture_score: self.read_i32("Game::facility_capture_score")?,
+            vehicle_elimination_score: self.read_i32("Game::vehicle_elimination_score")?,
+            action_detection_interval: self.read_i32("Game::action_detection_interval")?,
+            base_action_count: self.read_i32("Game::base_action_count")?,
+            additional_action_count_per_control_center: self.read_i32("Game::additional_action_count_per_control_center")?,
+            max_unit_group: self.read_i32("Game::max_unit_group")?,
+            terrain_weather_map_column_count: self.read_i32("Game::terrain_weather_map_column_count")?,
+            terrain_weather_map_row_count: self.read_i32("Game::terrain_weather_map_row_count")?,
+            plain_terrain_vision_factor: self.read_f64("Game::plain_terrain_vision_factor")?,
+            plain_terrain_stealth_factor: self.read_f64("Game::plain_terrain_stealth_factor")?,
+            plain_terrain_speed_factor: self.read_f64("Game::plain_terrain_speed_factor")?,
+            swamp_terrain_vision_factor: self.read_f64("Game::swamp_terrain_vision_factor")?,
+            swamp_terrain_stealth_factor: self.read_f64("Game::swamp_terrain_stealth_factor")?,
+            swamp_terrain_speed_factor: self.read_f64("Game::swamp_terrain_speed_factor")?,
+            forest_terrain_vision_factor: self.read_f64("Game::forest_terrain_vision_factor")?,
+            forest_terrain_stealth_factor: self.read_f64("Game::forest_terrain_stealth_factor")?,
+            forest_terrain_speed_factor: self.read_f64("Game::forest_terrain_speed_factor")?,
+            clear_weather_vision_factor: self.read_f64("Game::clear_weather_vision_factor")?,
+            clear_weather_stealth_factor: self.read_f64("Game::clear_weather_stealth_factor")?,
+            clear_weather_speed_factor: self.read_f64("Game::clear_weather_speed_factor")?,
+            cloud_weather_vision_factor: self.read_f64("Game::cloud_weather_vision_factor")?,
+            cloud_weather_stealth_factor: self.read_f64("Game::cloud_weather_stealth_factor")?,
+            cloud_weather_speed_factor: self.read_f64("Game::cloud_weather_speed_factor")?,
+            rain_weather_vision_factor: self.read_f64("Game::rain_weather_vision_factor")?,
+            rain_weather_stealth_factor: self.read_f64("Game::rain_weather_stealth_factor")?,
+            rain_weather_speed_factor: self.read_f64("Game::rain_weather_speed_factor")?,
+            vehicle_radius: self.read_f64("Game::vehicle_radius")?,
+            tank_durability: self.read_i32("Game::tank_durability")?,
+            tank_speed: self.read_f64("Game::tank_speed")?,
+            tank_vision_range: self.read_f64("Game::tank_vision_range")?,
+            tank_ground_attack_range: self.read_f64("Game::tank_ground_attack_range")?,
+            tank_aerial_attack_range: self.read_f64("Game::tank_aerial_attack_range")?,
+            tank_ground_damage: self.read_i32("Game::tank_ground_damage")?,
+            tank_aerial_damage: self.read_i32("Game::tank_aerial_damage")?,
+            tank_ground_defence: self.read_i32("Game::tank_ground_defence")?,
+            tank_aerial_defence: self.read_i32("Game::tank_aerial_defence")?,
+            tank_attack_cooldown_ticks: self.read_i32("Game::tank_attack_cooldown_ticks")?,
+            tank_production_cost: self.read_i32("Game::tank_production_cost")?,
+            ifv_durability: self.read_i32("Game::ifv_durability")?,
+            ifv_speed: self.read_f64("Game::ifv_speed")?,
+            ifv_vision_range: self.read_f64("Game::ifv_vision_range")?,
+            ifv_ground_attack_range: self.read_f64("Game::ifv_ground_attack_range")?,
+            ifv_aerial_attack_range: self.read_f64("Game::ifv_aerial_attack_range")?,
+            ifv_ground_damage: self.read_i32("Game::ifv_ground_damage")?,
+            ifv_aerial_damage: self.read_i32("Game::ifv_aerial_damage")?,
+            ifv_ground_defence: self.read_i32("Game::ifv_ground_defence")?,
+            ifv_aerial_defence: self.read_i32("Game::ifv_aerial_defence")?,
+            ifv_attack_cooldown_ticks: self.read_i32("Game::ifv_attack_cooldown_ticks")?,
+            ifv_production_cost: self.read_i32("Game::ifv_production_cost")?,
+            arrv_durability: self.read_i32("Game::arrv_durability")?,
+            arrv_speed: self.read_f64("Game::arrv_speed")?,
+            arrv_vision_range: self.read_f64("Game::arrv_vision_range")?,
+            arrv_ground_defence: self.read_i32("Game::arrv_ground_defence")?,
+            arrv_aerial_defence: self.read_i32("Game::arrv_aerial_defence")?,
+            arrv_production_cost: self.read_i32("Game::arrv_production_cost")?,
+            arrv_repair_range: self.read_f64("Game::arrv_repair_range")?,
+            arrv_repair_speed: self.read_f64("Game::arrv_repair_speed")?,
+            helicopter_durability: self.read_i32("Game::helicopter_durability")?,
+            helicopter_speed: self.read_f64("Game::helicopter_speed")?,
+            helicopter_vision_range: self.read_f64("Game::helicopter_vision_range")?,
+            helicopter_ground_attack_range: self.read_f64("Game::helicopter_ground_attack_range")?,
+            helicopter_aerial_attack_range: self.read_f64("Game::helicopter_aerial_attack_range")?,
+            helicopter_ground_damage: self.read_i32("Game::helicopter_ground_damage")?,
+            helicopter_aerial_damage: self.read_i32("Game::helicopter_aerial_damage")?,
+            helicopter_ground_defence: self.read_i32("Game::helicopter_ground_defence")?,
+            helicopter_aerial_defence: self.read_i32("Game::helicopter_aerial_defence")?,
+            helicopter_attack_cooldown_ticks: self.read_i32("Game::helicopter_attack_cooldown_ticks")?,
+            helicopter_production_cost: self.read_i32("Game::helicopter_production_cost")?,
+            fighter_durability: self.read_i32("Game::fighter_durability")?,
+            fighter_speed: self.read_f64("Game::fighter_speed")?,
+            fighter_vision_range: self.read_f64("Game::fighter_vision_range")?,
+            fighter_ground_attack_range: self.read_f64("Game::fighter_ground_attack_range")?,
+            fighter_aerial_attack_range: self.read_f64("Game::fighter_aerial_attack_range")?,
+            fighter_ground_damage: self.read_i32("Game::fighter_ground_damage")?,
+            fighter_aerial_damage: self.read_i32("Game::fighter_aerial_damage")?,
+            fighter_ground_defence: self.read_i32("Game::fighter_ground_defence")?,
+            fighter_aerial_defence: self.read_i32("Game::fighter_aerial_defence")?,
+            fighter_attack_cooldown_ticks: self.read_i32("Game::fighter_attack_cooldown_ticks")?,
+            fighter_production_cost: self.read_i32("Game::fighter_production_cost")?,
+            max_facility_capture_points: self.read_f64("Game::max_facility_capture_points")?,
+            facility_capture_points_per_vehicle_per_tick: self.read_f64("Game::facility_capture_points_per_vehicle_per_tick")?,
+            facility_width: self.read_f64("Game::facility_width")?,
+            facility_height: self.read_f64("Game::facility_height")?,
+            base_tactical_nuclear_strike_cooldown: self.read_i32("Game::base_tactical_nuclear_strike_cooldown")?,
+            tactical_nuclear_strike_cooldown_decrease_per_control_center: self.read_i32("Game::tactical_nuclear_strike_cooldown_decrease_per_control_center")?,
+            max_tactical_nuclear_strike_damage: self.read_f64("Game::max_tactical_nuclear_strike_damage")?,
+            tactical_nuclear_strike_radius: self.read_f64("Game::tactical_nuclear_strike_radius")?,
+            tactical_nuclear_strike_delay: self.read_i32("Game::tactical_nuclear_strike_delay")?,
         };
 
         Ok(result)
     }
 
-    fn read_player_context(&mut self) -> io::Result<PlayerContext> {
-        use std::io::{Error, ErrorKind};
-
-        if !self.read_bool()? {
-            return Err(Error::new(ErrorKind::Other, "RemoteProcessClient::read_player_context error: value is false"));
+    fn read_player_context(&mut self) -> ProtocolResult<PlayerContext> {
+        if !self.read_bool("PlayerContext")? {
+            return Err(ProtocolError::NullValue { path: "PlayerContext" });
         }
 
         let result = PlayerContext {
-            player: self.read_player()?,
+            player: self.read_player("PlayerContext::player")?,
             world: self.read_world()?,
         };
 
         Ok(result)
     }
 
-    fn read_player(&mut self) -> io::Result<Player> {
-        use std::io::{Error, ErrorKind};
-
-        match self.read_u8()? {
-            0 => return Err(Error::new(ErrorKind::Other, "RemoteProcessClient::read_player error: value is 0")),
+    fn read_player(&mut self, path: &'static str) -> ProtocolResult<Player> {
+        match self.read_u8(path)? {
+            0 => return Err(ProtocolError::NullValue { path }),
             127 => {
-                let id = self.read_i64()?;
-                return Ok(self.cache.players_by_id[&id].clone());
+                let id = self.read_i64(path)?;
+                return self.read_cache.players_by_id.get(&id).cloned()
+                    .ok_or(ProtocolError::UnknownPlayerId { path, id });
             },
             _ => {},
         }
 
         let result = Player {
-            id: self.read_i64()?,
-            me: self.read_bool()?,
-            strategy_crashed: self.read_bool()?,
-            score: self.read_i32()?,
-            remaining_action_cooldown_ticks: self.read_i32()?,
-            remaining_nuclear_strike_cooldown_ticks: self.read_i32()?,
-            next_nuclear_strike_vehicle_id: self.read_i64()?,
-            next_nuclear_strike_tick_index: self.read_i32()?,
-            next_nuclear_strike_x: self.read_f64()?,
-            next_nuclear_strike_y: self.read_f64()?,
+            id: self.read_i64("Player::id")?,
+            me: self.read_bool("Player::me")?,
+            strategy_crashed: self.read_bool("Player::strategy_crashed")?,
+            score: self.read_i32("Player::score")?,
+            remaining_action_cooldown_ticks: self.read_i32("Player::remaining_action_cooldown_ticks")?,
+            remaining_nuclear_strike_cooldown_ticks: self.read_i32("Player::remaining_nuclear_strike_cooldown_ticks")?,
+            next_nuclear_strike_vehicle_id: self.read_i64("Player::next_nuclear_strike_vehicle_id")?,
+            next_nuclear_strike_tick_index: self.read_i32("Player::next_nuclear_strike_tick_index")?,
+            next_nuclear_strike_x: self.read_f64("Player::next_nuclear_strike_x")?,
+            next_nuclear_strike_y: self.read_f64("Player::next_nuclear_strike_y")?,
         };
 
-        self.cache.players_by_id.insert(result.id, result.clone());
+        self.read_cache.players_by_id.insert(result.id, result.clone());
 
         Ok(result)
     }
 
-    fn read_world(&mut self) -> io::Result<World> {
-        use std::io::{Error, ErrorKind};
-
-        if !self.read_bool()? {
-            return Err(Error::new(ErrorKind::Other, "RemoteProcessClient::read_world error: value is false"));
+    fn read_world(&mut self) -> ProtocolResult<World> {
+        if !self.read_bool("World")? {
+            return Err(ProtocolError::NullValue { path: "World" });
         }
 
         let result = World {
-            tick_index: self.read_i32()?,
-            tick_count: self.read_i32()?,
-            width: self.read_f64()?,
-            height: self.read_f64()?,
+            tick_index: self.read_i32("World::tick_index")?,
+            tick_count: self.read_i32("World::tick_count")?,
+            width: self.read_f64("World::width")?,
+            height: self.read_f64("World::height")?,
             players: self.read_players()?,
             new_vehicles: self.read_vehicles()?,
             vehicle_updates: self.read_vehicles_update()?,
             terrain_by_cell_x_y: {
-                if self.cache.terrain_by_cell_x_y.is_empty() {
-                    self.cache.terrain_by_cell_x_y = self.read_terrain_types_2d()?;
+                if self.read_cache.terrain_by_cell_x_y.is_empty() {
+                    self.read_cache.terrain_by_cell_x_y = self.read_terrain_types_2d()?;
                 }
-                self.cache.terrain_by_cell_x_y.clone()
+                self.read_cache.terrain_by_cell_x_y.clone()
             },
             weather_by_cell_x_y: {
-                if self.cache.weather_by_cell_x_y.is_empty() {
-                    self.cache.weather_by_cell_x_y = self.read_weather_types_2d()?;
+                if self.read_cache.weather_by_cell_x_y.is_empty() {
+                    self.read_cache.weather_by_cell_x_y = self.read_weather_types_2d()?;
                 }
-                self.cache.weather_by_cell_x_y.clone()
+                self.read_cache.weather_by_cell_x_y.clone()
             },
             facilities: self.read_facilities()?,
         };
@@ -343,167 +393,198 @@ impl RemoteProcessClient {
         Ok(result)
     }
 
-    fn read_vehicle(&mut self) -> io::Result<Vehicle> {
-        use std::io::{Error, ErrorKind};
-
-        if !self.read_bool()? {
-            return Err(Error::new(ErrorKind::Other, "RemoteProcessClient::read_vehicle error: value is false"));
+    fn read_vehicle(&mut self) -> ProtocolResult<Vehicle> {
+        if !self.read_bool("Vehicle")? {
+            return Err(ProtocolError::NullValue { path: "Vehicle" });
         }
 
         let result = Vehicle {
-            id: self.read_i64()?,
-            x: self.read_f64()?,
-            y: self.read_f64()?,
-            radius: self.read_f64()?,
-            player_id: self.read_i64()?,
-            durability: self.read_i32()?,
-            max_durability: self.read_i32()?,
-            max_speed: self.read_f64()?,
-            vision_range: self.read_f64()?,
-            squared_vision_range: self.read_f64()?,
-            ground_attack_range: self.read_f64()?,
-            squared_ground_attack_range: self.read_f64()?,
-            aerial_attack_range: self.read_f64()?,
-            squared_aerial_attack_range: self.read_f64()?,
-            ground_damage: self.read_i32()?,
-            aerial_damage: self.read_i32()?,
-            ground_defence: self.read_i32()?,
-            aerial_defence: self.read_i32()?,
-            attack_cooldown_ticks: self.read_i32()?,
-            remaining_attack_cooldown_ticks: self.read_i32()?,
-            kind: self.read_vehicle_type()?,
-            aerial: self.read_bool()?,
-            selected: self.read_bool()?,
-            groups: self.read_vec_i32()?,
+            id: self.read_i64("Vehicle::id")?,
+            x: self.read_f64("Vehicle::x")?,
+            y: self.read_f64("Vehicle::y")?,
+            radius: self.read_f64("Vehicle::radius")?,
+            player_id: self.read_i64("Vehicle::player_id")?,
+            durability: self.read_i32("Vehicle::durability")?,
+            max_durability: self.read_i32("Vehicle::max_durability")?,
+            max_speed: self.read_f64("Vehicle::max_speed")?,
+            vision_range: self.read_f64("Vehicle::vision_range")?,
+            squared_vision_range: self.read_f64("Vehicle::squared_vision_range")?,
+            ground_attack_range: self.read_f64("Vehicle::ground_attack_range")?,
+            squared_ground_attack_range: self.read_f64("Vehicle::squared_ground_attack_range")?,
+            aerial_attack_range: self.read_f64("Vehicle::aerial_attack_range")?,
+            squared_aerial_attack_range: self.read_f64("Vehicle::squared_aerial_attack_range")?,
+            ground_damage: self.read_i32("Vehicle::ground_damage")?,
+            aerial_damage: self.read_i32("Vehicle::aerial_damage")?,
+            ground_defence: self.read_i32("Vehicle::ground_defence")?,
+            aerial_defence: self.read_i32("Vehicle::aerial_defence")?,
+            attack_cooldown_ticks: self.read_i32("Vehicle::attack_cooldown_ticks")?,
+            remaining_attack_cooldown_ticks: self.read_i32("Vehicle::remaining_attack_cooldown_ticks")?,
+            kind: self.read_vehicle_type("Vehicle::kind")?,
+            aerial: self.read_bool("Vehicle::aerial")?,
+            selected: self.read_bool("Vehicle::selected")?,
+            groups: self.read_vec_i32("Vehicle::groups")?,
         };
 
         Ok(result)
     }
 
-    fn read_vehicle_update(&mut self) -> io::Result<VehicleUpdate> {
-        use std::io::{Error, ErrorKind};
-
-        if !self.read_bool()? {
-            return Err(Error::new(ErrorKind::Other, "RemoteProcessClient::read_vehicle_update error: value is false"));
+    fn read_vehicle_update(&mut self) -> ProtocolResult<VehicleUpdate> {
+        if !self.read_bool("VehicleUpdate")? {
+            return Err(ProtocolError::NullValue { path: "VehicleUpdate" });
         }
 
         let result = VehicleUpdate {
-            id: self.read_i64()?,
-            x: self.read_f64()?,
-            y: self.read_f64()?,
-            durability: self.read_i32()?,
-            remaining_attack_cooldown_ticks: self.read_i32()?,
-            selected: self.read_bool()?,
-            groups: self.read_vec_i32()?,
+            id: self.read_i64("VehicleUpdate::id")?,
+            x: self.read_f64("VehicleUpdate::x")?,
+            y: self.read_f64("VehicleUpdate::y")?,
+            durability: self.read_i32("VehicleUpdate::durability")?,
+            remaining_attack_cooldown_ticks: self.read_i32("VehicleUpdate::remaining_attack_cooldown_ticks")?,
+            selected: self.read_bool("VehicleUpdate::selected")?,
+            groups: self.read_vec_i32("VehicleUpdate::groups")?,
         };
 
         Ok(result)
     }
 
-    fn read_facility(&mut self) -> io::Result<Facility> {
-        use std::io::{Error, ErrorKind};
-
-        match self.read_u8()? {
-            0 => return Err(Error::new(ErrorKind::Other, "RemoteProcessClient::read_facility error: value is 0")),
+    fn read_facility(&mut self, path: &'static str) -> ProtocolResult<Facility> {
+        match self.read_u8(path)? {
+            0 => return Err(ProtocolError::NullValue { path }),
             127 => {
-                let id = self.read_i64()?;
-                return Ok(self.cache.facilities_by_id[&id].clone());
+                let id = self.read_i64(path)?;
+                return self.read_cache.facilities_by_id.get(&id).cloned()
+                    .ok_or(ProtocolError::UnknownFacilityId { path, id });
             },
             _ => {},
         }
 
         let result = Facility {
-            id: self.read_i64()?,
-            kind: self.read_facility_type()?,
-            owner_player_id: self.read_i64()?,
-            left: self.read_f64()?,
-            top: self.read_f64()?,
-            capture_points: self.read_f64()?,
-            vehicle_type: self.read_vehicle_type()?,
-            production_progress: self.read_i32()?,
+            id: self.read_i64("Facility::id")?,
+            kind: self.read_facility_type("Facility::kind")?,
+            owner_player_id: self.read_i64("Facility::owner_player_id")?,
+            left: self.read_f64("Facility::left")?,
+            top: self.read_f64("Facility::top")?,
+            capture_points: self.read_f64("Facility::capture_points")?,
+            vehicle_type: self.read_vehicle_type("Facility::vehicle_type")?,
+            production_progress: self.read_i32("Facility::production_progress")?,
         };
 
-        self.cache.facilities_by_id.insert(result.id, result.clone());
+        self.read_cache.facilities_by_id.insert(result.id, result.clone());
 
         Ok(result)
     }
 
+    fn read_action(&mut self) -> ProtocolResult<Action> {
+        if !self.read_bool("Action")? {
+            return Err(ProtocolError::NullValue { path: "Action" });
+        }
+
+        let result = Action {
+            action: self.read_action_type("Action::action")?,
+            group: self.read_i32("Action::group")?,
+            left: self.read_f64("Action::left")?,
+            top: self.read_f64("Action::top")?,
+            right: self.read_f64("Action::right")?,
+            bottom: self.read_f64("Action::bottom")?,
+            x: self.read_f64("Action::x")?,
+            y: self.read_f64("Action::y")?,
+            angle: self.read_f64("Action::angle")?,
+            factor: self.read_f64("Action::factor")?,
+            max_speed: self.read_f64("Action::max_speed")?,
+            max_angular_speed: self.read_f64("Action::max_angular_speed")?,
+            vehicle_type: self.read_vehicle_type("Action::vehicle_type")?,
+            facility_id: self.read_i64("Action::facility_id")?,
+            vehicle_id: self.read_i64("Action::vehicle_id")?,
+        };
+
+        Ok(result)
+    }
+
+    fn read_string(&mut self, path: &'static str) -> ProtocolResult<String> {
+        let bytes = self.read_vec(path, |s| s.read_u8(path))?;
+        String::from_utf8(bytes).map_err(|error| ProtocolError::InvalidString { path, error })
+    }
+
+    read_option_enum_impl!(read_action_type, ActionType,
+        None, ClearAndSelect, AddToSelection, Deselect, Assign, Dismiss, Disband, Move, Rotate, Scale,
+        SetupVehicleProduction, TacticalNuclearStrike);
+
     read_option_enum_impl!(read_facility_type, FacilityType,
-        "RemoteProcessClient::read_facility_type error: invalid FacilityType value: {}",
         ControlCenter, VehicleFactory);
 
     read_option_enum_impl!(read_vehicle_type, VehicleType,
-        "RemoteProcessClient::read_vehicle_type error: invalid VehicleType value: {}",
         Arrv, Fighter, Helicopter, Ifv, Tank);
 
     read_enum_impl!(read_terrain_type, TerrainType,
-        "RemoteProcessClient::read_terrain_type error: invalid TerrainType value: {}",
         Plain, Swamp, Forest);
 
     read_enum_impl!(read_weather_type, WeatherType,
-        "RemoteProcessClient::read_weather_type error: invalid WeatherType value: {}",
         Clear, Cloud, Rain);
 
-    fn read_players(&mut self) -> io::Result<Vec<Player>> {
-        let len = self.read_i32()?;
+    fn read_players(&mut self) -> ProtocolResult<Arc<[Player]>> {
+        const PATH: &str = "World::players";
+        let len = self.read_i32(PATH)?;
         if len < 0 {
-            Ok(self.cache.players.clone())
+            Ok(self.read_cache.players.clone())
         } else {
-            let players = self.read_vec_impl(len as usize, |s| s.read_player())?;
-            self.cache.players = players.clone();
+            let players: Arc<[Player]> = Arc::from(self.read_vec_impl(len as usize, |s| s.read_player(PATH))?);
+            self.read_cache.players = players.clone();
             Ok(players)
         }
     }
 
-    fn read_vehicles(&mut self) -> io::Result<Vec<Vehicle>> {
-        self.read_vec(|s| s.read_vehicle())
+    fn read_vehicles(&mut self) -> ProtocolResult<Vec<Vehicle>> {
+        self.read_vec("World::new_vehicles", |s| s.read_vehicle())
     }
 
-    fn read_vehicles_update(&mut self) -> io::Result<Vec<VehicleUpdate>> {
-        self.read_vec(|s| s.read_vehicle_update())
+    fn read_vehicles_update(&mut self) -> ProtocolResult<Vec<VehicleUpdate>> {
+        self.read_vec("World::vehicle_updates", |s| s.read_vehicle_update())
     }
 
-    fn read_terrain_types_2d(&mut self) -> io::Result<Vec<Vec<TerrainType>>> {
-        self.read_vec(|s| s.read_vec(|ss| ss.read_terrain_type()))
+    fn read_terrain_types_2d(&mut self) -> ProtocolResult<Grid<TerrainType>> {
+        const PATH: &str = "World::terrain_by_cell_x_y";
+        let columns = self.read_vec(PATH, |s| s.read_vec(PATH, |ss| ss.read_terrain_type(PATH)))?;
+        Grid::from_columns(&columns).ok_or(ProtocolError::NonRectangularGrid { path: PATH })
     }
 
-    fn read_weather_types_2d(&mut self) -> io::Result<Vec<Vec<WeatherType>>> {
-        self.read_vec(|s| s.read_vec(|ss| ss.read_weather_type()))
+    fn read_weather_types_2d(&mut self) -> ProtocolResult<Grid<WeatherType>> {
+        const PATH: &str = "World::weather_by_cell_x_y";
+        let columns = self.read_vec(PATH, |s| s.read_vec(PATH, |ss| ss.read_weather_type(PATH)))?;
+        Grid::from_columns(&columns).ok_or(ProtocolError::NonRectangularGrid { path: PATH })
     }
 
-    fn read_facilities(&mut self) -> io::Result<Vec<Facility>> {
-        let len = self.read_i32()?;
+    fn read_facilities(&mut self) -> ProtocolResult<Arc<[Facility]>> {
+        const PATH: &str = "World::facilities";
+        let len = self.read_i32(PATH)?;
         if len < 0 {
-            Ok(self.cache.facilities.clone())
+            Ok(self.read_cache.facilities.clone())
         } else {
-            let facilities = self.read_vec_impl(len as usize, |s| s.read_facility())?;
-            self.cache.facilities = facilities.clone();
+            let facilities: Arc<[Facility]> = Arc::from(self.read_vec_impl(len as usize, |s| s.read_facility(PATH))?);
+            self.read_cache.facilities = facilities.clone();
             Ok(facilities)
         }
     }
 
-    fn read_vec_i32(&mut self) -> io::Result<Vec<i32>> {
-        self.read_vec(|s| s.read_i32())
+    fn read_vec_i32(&mut self, path: &'static str) -> ProtocolResult<Vec<i32>> {
+        self.read_vec(path, |s| s.read_i32(path))
     }
 
     #[inline]
-    fn read_bool(&mut self) -> io::Result<bool> {
-        Ok(self.read_u8()? != 0)
+    fn read_bool(&mut self, path: &'static str) -> ProtocolResult<bool> {
+        Ok(self.read_u8(path)? != 0)
     }
 
-    fn read_vec<T, F>(&mut self, read: F) -> io::Result<Vec<T>>
-        where F: FnMut(&mut Self) -> io::Result<T> {
-        use std::io::{Error, ErrorKind};
-        let len = self.read_i32()?;
+    fn read_vec<T, F>(&mut self, path: &'static str, read: F) -> ProtocolResult<Vec<T>>
+        where F: FnMut(&mut Self) -> ProtocolResult<T> {
+        let len = self.read_i32(path)?;
         if len < 0 {
-            return Err(Error::new(ErrorKind::Other, format!("RemoteProcessClient::read_vec error: len < 0, where len={}", len)));
+            return Err(ProtocolError::NegativeLength { path, len });
         }
         self.read_vec_impl(len as usize, read)
     }
 
-    fn read_vec_impl<T, F>(&mut self, len: usize, mut read: F) -> io::Result<Vec<T>>
-        where F: FnMut(&mut Self) -> io::Result<T> {
-        let mut result = Vec::with_capacity(len);
+    fn read_vec_impl<T, F>(&mut self, len: usize, mut read: F) -> ProtocolResult<Vec<T>>
+        where F: FnMut(&mut Self) -> ProtocolResult<T> {
+        let mut result = Vec::with_capacity(len.min(MAX_PREALLOCATED_LEN));
         for _ in 0..len {
             result.push(read(self)?);
         }
@@ -511,31 +592,31 @@ impl RemoteProcessClient {
     }
 
     #[inline]
-    fn read_u8(&mut self) -> io::Result<(u8)> {
-        self.reader.read_u8()
+    fn read_u8(&mut self, path: &'static str) -> ProtocolResult<u8> {
+        self.reader.read_u8().map_err(|e| ProtocolError::from(e).truncated_at(path))
     }
 
     #[inline]
-    fn read_i8(&mut self) -> io::Result<(i8)> {
-        self.reader.read_i8()
+    fn read_i8(&mut self, path: &'static str) -> ProtocolResult<i8> {
+        self.reader.read_i8().map_err(|e| ProtocolError::from(e).truncated_at(path))
     }
 
     #[inline]
-    fn read_i32(&mut self) -> io::Result<(i32)> {
-        self.reader.read_i32::<LittleEndian>()
+    fn read_i32(&mut self, path: &'static str) -> ProtocolResult<i32> {
+        self.reader.read_i32::<LittleEndian>().map_err(|e| ProtocolError::from(e).truncated_at(path))
     }
 
     #[inline]
-    fn read_i64(&mut self) -> io::Result<(i64)> {
-        self.reader.read_i64::<LittleEndian>()
+    fn read_i64(&mut self, path: &'static str) -> ProtocolResult<i64> {
+        self.reader.read_i64::<LittleEndian>().map_err(|e| ProtocolError::from(e).truncated_at(path))
     }
 
     #[inline]
-    fn read_f64(&mut self) -> io::Result<(f64)> {
-        self.reader.read_f64::<LittleEndian>()
+    fn read_f64(&mut self, path: &'static str) -> ProtocolResult<f64> {
+        self.reader.read_f64::<LittleEndian>().map_err(|e| ProtocolError::from(e).truncated_at(path))
     }
 
-    fn write_message(&mut self, value: &Message) -> io::Result<()> {
+    pub fn write_message(&mut self, value: &Message) -> io::Result<()> {
         self.write_message_id(value.get_id())?;
         self.write_message_content(value)?;
         self.flush()
@@ -546,19 +627,19 @@ impl RemoteProcessClient {
     }
 
     fn write_message_content(&mut self, value: &Message) -> io::Result<()> {
-        match value {
-            &Message::UnknownMessage => unimplemented!(),
-            &Message::GameOver => unimplemented!(),
-            &Message::AuthenticationToken(ref v) => self.write_authentication_token(v),
-            &Message::TeamSize(_) => unimplemented!(),
-            &Message::ProtocolVersion(v) => self.write_i32(v),
-            &Message::GameContext(ref _v) => unimplemented!(),
-            &Message::PlayerContext(ref _v) => unimplemented!(),
-            &Message::ActionMessage(ref v) => self.write_action(v),
+        match *value {
+            Message::UnknownMessage => Ok(()),
+            Message::GameOver => Ok(()),
+            Message::AuthenticationToken(ref v) => self.write_authentication_token(v),
+            Message::TeamSize(v) => self.write_i32(v),
+            Message::ProtocolVersion(v) => self.write_i32(v),
+            Message::GameContext(ref v) => self.write_game(v),
+            Message::PlayerContext(ref v) => self.write_player_context(v),
+            Message::ActionMessage(ref v) => self.write_action(v),
         }
     }
 
-    fn write_authentication_token(&mut self, value: &String) -> io::Result<()> {
+    fn write_authentication_token(&mut self, value: &str) -> io::Result<()> {
         self.write_i32(value.len() as i32)?;
 
         for b in value.bytes() {
@@ -568,6 +649,262 @@ impl RemoteProcessClient {
         Ok(())
     }
 
+    fn write_game(&mut self, value: &Game) -> io::Result<()> {
+        self.write_bool(true)?;
+        self.write_i64(value.random_seed)?;
+        self.write_i32(value.tick_count)?;
+        self.write_f64(value.world_width)?;
+        self.write_f64(value.world_height)?;
+        self.write_bool(value.fog_of_war_enabled)?;
+        self.write_i32(value.victory_score)?;
+        self.write_i32(value.facility_capture_score)?;
+        self.write_i32(value.vehicle_elimination_score)?;
+        self.write_i32(value.action_detection_interval)?;
+        self.write_i32(value.base_action_count)?;
+        self.write_i32(value.additional_action_count_per_control_center)?;
+        self.write_i32(value.max_unit_group)?;
+        self.write_i32(value.terrain_weather_map_column_count)?;
+        self.write_i32(value.terrain_weather_map_row_count)?;
+        self.write_f64(value.plain_terrain_vision_factor)?;
+        self.write_f64(value.plain_terrain_stealth_factor)?;
+        self.write_f64(value.plain_terrain_speed_factor)?;
+        self.write_f64(value.swamp_terrain_vision_factor)?;
+        self.write_f64(value.swamp_terrain_stealth_factor)?;
+        self.write_f64(value.swamp_terrain_speed_factor)?;
+        self.write_f64(value.forest_terrain_vision_factor)?;
+        self.write_f64(value.forest_terrain_stealth_factor)?;
+        self.write_f64(value.forest_terrain_speed_factor)?;
+        self.write_f64(value.clear_weather_vision_factor)?;
+        self.write_f64(value.clear_weather_stealth_factor)?;
+        self.write_f64(value.clear_weather_speed_factor)?;
+        self.write_f64(value.cloud_weather_vision_factor)?;
+        self.write_f64(value.cloud_weather_stealth_factor)?;
+        self.write_f64(value.cloud_weather_speed_factor)?;
+        self.write_f64(value.rain_weather_vision_factor)?;
+        self.write_f64(value.rain_weather_stealth_factor)?;
+        self.write_f64(value.rain_weather_speed_factor)?;
+        self.write_f64(value.vehicle_radius)?;
+        self.write_i32(value.tank_durability)?;
+        self.write_f64(value.tank_speed)?;
+        self.write_f64(value.tank_vision_range)?;
+        self.write_f64(value.tank_ground_attack_range)?;
+        self.write_f64(value.tank_aerial_attack_range)?;
+        self.write_i32(value.tank_ground_damage)?;
+        self.write_i32(value.tank_aerial_damage)?;
+        self.write_i32(value.tank_ground_defence)?;
+        self.write_i32(value.tank_aerial_defence)?;
+        self.write_i32(value.tank_attack_cooldown_ticks)?;
+        self.write_i32(value.tank_production_cost)?;
+        self.write_i32(value.ifv_durability)?;
+        self.write_f64(value.ifv_speed)?;
+        self.write_f64(value.ifv_vision_range)?;
+        self.write_f64(value.ifv_ground_attack_range)?;
+        self.write_f64(value.ifv_aerial_attack_range)?;
+        self.write_i32(value.ifv_ground_damage)?;
+        self.write_i32(value.ifv_aerial_damage)?;
+        self.write_i32(value.ifv_ground_defence)?;
+        self.write_i32(value.ifv_aerial_defence)?;
+        self.write_i32(value.ifv_attack_cooldown_ticks)?;
+        self.write_i32(value.ifv_production_cost)?;
+        self.write_i32(value.arrv_durability)?;
+        self.write_f64(value.arrv_speed)?;
+        self.write_f64(value.arrv_vision_range)?;
+        self.write_i32(value.arrv_ground_defence)?;
+        self.write_i32(value.arrv_aerial_defence)?;
+        self.write_i32(value.arrv_production_cost)?;
+        self.write_f64(value.arrv_repair_range)?;
+        self.write_f64(value.arrv_repair_speed)?;
+        self.write_i32(value.helicopter_durability)?;
+        self.write_f64(value.helicopter_speed)?;
+        self.write_f64(value.helicopter_vision_range)?;
+        self.write_f64(value.helicopter_ground_attack_range)?;
+        self.write_f64(value.helicopter_aerial_attack_range)?;
+        self.write_i32(value.helicopter_ground_damage)?;
+        self.write_i32(value.helicopter_aerial_damage)?;
+        self.write_i32(value.helicopter_ground_defence)?;
+        self.write_i32(value.helicopter_aerial_defence)?;
+        self.write_i32(value.helicopter_attack_cooldown_ticks)?;
+        self.write_i32(value.helicopter_production_cost)?;
+        self.write_i32(value.fighter_durability)?;
+        self.write_f64(value.fighter_speed)?;
+        self.write_f64(value.fighter_vision_range)?;
+        self.write_f64(value.fighter_ground_attack_range)?;
+        self.write_f64(value.fighter_aerial_attack_range)?;
+        self.write_i32(value.fighter_ground_damage)?;
+        self.write_i32(value.fighter_aerial_damage)?;
+        self.write_i32(value.fighter_ground_defence)?;
+        self.write_i32(value.fighter_aerial_defence)?;
+        self.write_i32(value.fighter_attack_cooldown_ticks)?;
+        self.write_i32(value.fighter_production_cost)?;
+        self.write_f64(value.max_facility_capture_points)?;
+        self.write_f64(value.facility_capture_points_per_vehicle_per_tick)?;
+        self.write_f64(value.facility_width)?;
+        self.write_f64(value.facility_height)?;
+        self.write_i32(value.base_tactical_nuclear_strike_cooldown)?;
+        self.write_i32(value.tactical_nuclear_strike_cooldown_decrease_per_control_center)?;
+        self.write_f64(value.max_tactical_nuclear_strike_damage)?;
+        self.write_f64(value.tactical_nuclear_strike_radius)?;
+        self.write_i32(value.tactical_nuclear_strike_delay)?;
+        Ok(())
+    }
+
+    fn write_player_context(&mut self, value: &PlayerContext) -> io::Result<()> {
+        self.write_bool(true)?;
+        self.write_player(&value.player)?;
+        self.write_world(&value.world)?;
+        Ok(())
+    }
+
+    fn write_player(&mut self, value: &Player) -> io::Result<()> {
+        if self.write_cache.players_by_id.get(&value.id) == Some(value) {
+            self.write_u8(127)?;
+            return self.write_i64(value.id);
+        }
+
+        self.write_u8(1)?;
+        self.write_i64(value.id)?;
+        self.write_bool(value.me)?;
+        self.write_bool(value.strategy_crashed)?;
+        self.write_i32(value.score)?;
+        self.write_i32(value.remaining_action_cooldown_ticks)?;
+        self.write_i32(value.remaining_nuclear_strike_cooldown_ticks)?;
+        self.write_i64(value.next_nuclear_strike_vehicle_id)?;
+        self.write_i32(value.next_nuclear_strike_tick_index)?;
+        self.write_f64(value.next_nuclear_strike_x)?;
+        self.write_f64(value.next_nuclear_strike_y)?;
+
+        self.write_cache.players_by_id.insert(value.id, value.clone());
+
+        Ok(())
+    }
+
+    fn write_world(&mut self, value: &World) -> io::Result<()> {
+        self.write_bool(true)?;
+        self.write_i32(value.tick_index)?;
+        self.write_i32(value.tick_count)?;
+        self.write_f64(value.width)?;
+        self.write_f64(value.height)?;
+        self.write_players(&value.players)?;
+        self.write_vec(&value.new_vehicles, |s, v| s.write_vehicle(v))?;
+        self.write_vec(&value.vehicle_updates, |s, v| s.write_vehicle_update(v))?;
+        if self.write_cache.terrain_by_cell_x_y.is_empty() {
+            self.write_grid(&value.terrain_by_cell_x_y)?;
+            self.write_cache.terrain_by_cell_x_y = value.terrain_by_cell_x_y.clone();
+        }
+        if self.write_cache.weather_by_cell_x_y.is_empty() {
+            self.write_grid(&value.weather_by_cell_x_y)?;
+            self.write_cache.weather_by_cell_x_y = value.weather_by_cell_x_y.clone();
+        }
+        self.write_facilities(&value.facilities)?;
+        Ok(())
+    }
+
+    fn write_vehicle(&mut self, value: &Vehicle) -> io::Result<()> {
+        self.write_bool(true)?;
+        self.write_i64(value.id)?;
+        self.write_f64(value.x)?;
+        self.write_f64(value.y)?;
+        self.write_f64(value.radius)?;
+        self.write_i64(value.player_id)?;
+        self.write_i32(value.durability)?;
+        self.write_i32(value.max_durability)?;
+        self.write_f64(value.max_speed)?;
+        self.write_f64(value.vision_range)?;
+        self.write_f64(value.squared_vision_range)?;
+        self.write_f64(value.ground_attack_range)?;
+        self.write_f64(value.squared_ground_attack_range)?;
+        self.write_f64(value.aerial_attack_range)?;
+        self.write_f64(value.squared_aerial_attack_range)?;
+        self.write_i32(value.ground_damage)?;
+        self.write_i32(value.aerial_damage)?;
+        self.write_i32(value.ground_defence)?;
+        self.write_i32(value.aerial_defence)?;
+        self.write_i32(value.attack_cooldown_ticks)?;
+        self.write_i32(value.remaining_attack_cooldown_ticks)?;
+        self.write_vehicle_type(value.kind)?;
+        self.write_bool(value.aerial)?;
+        self.write_bool(value.selected)?;
+        self.write_vec_i32(&value.groups)?;
+        Ok(())
+    }
+
+    fn write_vehicle_update(&mut self, value: &VehicleUpdate) -> io::Result<()> {
+        self.write_bool(true)?;
+        self.write_i64(value.id)?;
+        self.write_f64(value.x)?;
+        self.write_f64(value.y)?;
+        self.write_i32(value.durability)?;
+        self.write_i32(value.remaining_attack_cooldown_ticks)?;
+        self.write_bool(value.selected)?;
+        self.write_vec_i32(&value.groups)?;
+        Ok(())
+    }
+
+    fn write_facility(&mut self, value: &Facility) -> io::Result<()> {
+        if self.write_cache.facilities_by_id.get(&value.id) == Some(value) {
+            self.write_u8(127)?;
+            return self.write_i64(value.id);
+        }
+
+        self.write_u8(1)?;
+        self.write_i64(value.id)?;
+        self.write_option_enum(value.kind)?;
+        self.write_i64(value.owner_player_id)?;
+        self.write_f64(value.left)?;
+        self.write_f64(value.top)?;
+        self.write_f64(value.capture_points)?;
+        self.write_vehicle_type(value.vehicle_type)?;
+        self.write_i32(value.production_progress)?;
+
+        self.write_cache.facilities_by_id.insert(value.id, value.clone());
+
+        Ok(())
+    }
+
+    fn write_players(&mut self, value: &Arc<[Player]>) -> io::Result<()> {
+        if Arc::ptr_eq(&self.write_cache.players, value) || self.write_cache.players == *value {
+            self.write_i32(-1)
+        } else {
+            self.write_vec(value, |s, v| s.write_player(v))?;
+            self.write_cache.players = value.clone();
+            Ok(())
+        }
+    }
+
+    fn write_facilities(&mut self, value: &Arc<[Facility]>) -> io::Result<()> {
+        if Arc::ptr_eq(&self.write_cache.facilities, value) || self.write_cache.facilities == *value {
+            self.write_i32(-1)
+        } else {
+            self.write_vec(value, |s, v| s.write_facility(v))?;
+            self.write_cache.facilities = value.clone();
+            Ok(())
+        }
+    }
+
+    fn write_grid<T: Into<i8> + Copy>(&mut self, value: &Grid<T>) -> io::Result<()> {
+        self.write_i32(value.column_count() as i32)?;
+        for x in 0..value.column_count() {
+            self.write_i32(value.row_count() as i32)?;
+            for y in 0..value.row_count() {
+                self.write_enum(value[(x, y)])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_vec_i32(&mut self, value: &[i32]) -> io::Result<()> {
+        self.write_vec(value, |s, v| s.write_i32(*v))
+    }
+
+    fn write_vec<T, F>(&mut self, value: &[T], mut write: F) -> io::Result<()>
+        where F: FnMut(&mut Self, &T) -> io::Result<()> {
+        self.write_i32(value.len() as i32)?;
+        for v in value {
+            write(self, v)?;
+        }
+        Ok(())
+    }
+
     fn write_action(&mut self, value: &Action) -> io::Result<()> {
         self.write_bool(true)?;
         self.write_action_type(value.action)?;
@@ -607,6 +944,11 @@ impl RemoteProcessClient {
         }
     }
 
+    #[inline]
+    fn write_enum<T: Into<i8>>(&mut self, value: T) -> io::Result<()> {
+        self.write_i8(value.into())
+    }
+
     #[inline]
     fn write_bool(&mut self, value: bool) -> io::Result<()> {
         self.writer.write_u8(if value { 1 } else { 0 })
@@ -644,17 +986,85 @@ impl RemoteProcessClient {
     }
 }
 
-impl Into<i8> for ActionType {
+impl<R: io::Read, W: io::Write> RemoteProcessClient<R, W> {
+    pub fn read_authentication_token_message(&mut self) -> ProtocolResult<String> {
+        match self.read_message()? {
+            Message::AuthenticationToken(v) => Ok(v),
+            v => Err(ProtocolError::UnexpectedMessage {
+                expected: "Message::AuthenticationToken",
+                received: Box::new(v),
+            }),
+        }
+    }
+
+    pub fn read_protocol_version_message(&mut self) -> ProtocolResult<i32> {
+        match self.read_message()? {
+            Message::ProtocolVersion(v) => Ok(v),
+            v => Err(ProtocolError::UnexpectedMessage {
+                expected: "Message::ProtocolVersion",
+                received: Box::new(v),
+            }),
+        }
+    }
+
+    pub fn write_team_size_message(&mut self, team_size: i32) -> io::Result<()> {
+        self.write_message(&Message::TeamSize(team_size))
+    }
+
+    pub fn write_game_message(&mut self, game: Game) -> io::Result<()> {
+        self.write_message(&Message::GameContext(game))
+    }
+
+    pub fn write_player_context_message(&mut self, player_context: Option<PlayerContext>) -> io::Result<()> {
+        match player_context {
+            Some(v) => self.write_message(&Message::PlayerContext(v)),
+            None => self.write_message(&Message::GameOver),
+        }
+    }
+
+    pub fn read_action_message(&mut self) -> ProtocolResult<Action> {
+        match self.read_message()? {
+            Message::ActionMessage(v) => Ok(v),
+            v => Err(ProtocolError::UnexpectedMessage {
+                expected: "Message::ActionMessage",
+                received: Box::new(v),
+            }),
+        }
+    }
+}
+
+impl From<ActionType> for i8 {
+    #[inline]
+    fn from(value: ActionType) -> i8 {
+        value as i8
+    }
+}
+
+impl From<VehicleType> for i8 {
+    #[inline]
+    fn from(value: VehicleType) -> i8 {
+        value as i8
+    }
+}
+
+impl From<FacilityType> for i8 {
     #[inline]
-    fn into(self) -> i8 {
-        self as i8
+    fn from(value: FacilityType) -> i8 {
+        value as i8
     }
 }
 
-impl Into<i8> for VehicleType {
+impl From<TerrainType> for i8 {
     #[inline]
-    fn into(self) -> i8 {
-        self as i8
+    fn from(value: TerrainType) -> i8 {
+        value as i8
+    }
+}
+
+impl From<WeatherType> for i8 {
+    #[inline]
+    fn from(value: WeatherType) -> i8 {
+        value as i8
     }
 }
 
@@ -695,7 +1105,7 @@ pub trait ByteOrder: Clone + Copy + Debug + Default + Eq + Hash + Ord + PartialE
 
     #[inline]
     fn write_f64(buf: &mut [u8], n: f64) {
-        Self::write_u64(buf, unsafe { transmute(n) })
+        Self::write_u64(buf, n.to_bits())
     }
 }
 
@@ -741,8 +1151,8 @@ macro_rules! read_num_bytes {
 macro_rules! write_num_bytes {
     ($ty:ty, $size:expr, $n:expr, $dst:expr, $which:ident) => ({
         assert!($size <= $dst.len());
+        let bytes = $n.$which().to_ne_bytes();
         unsafe {
-            let bytes = transmute::<_, [u8; $size]>($n.$which());
             copy_nonoverlapping((&bytes).as_ptr(), $dst.as_mut_ptr(), $size);
         }
     });
@@ -772,15 +1182,13 @@ impl ByteOrder for LittleEndian {
 
 #[inline]
 fn safe_u64_bits_to_f64(u: u64) -> f64 {
-    use core::f64::NAN;
-
     const EXP_MASK: u64 = 0x7FF0000000000000;
     const FRACT_MASK: u64 = 0x000FFFFFFFFFFFFF;
 
     if u & EXP_MASK == EXP_MASK && u & FRACT_MASK != 0 {
-        NAN
+        f64::NAN
     } else {
-        unsafe { transmute(u) }
+        f64::from_bits(u)
     }
 }
 
@@ -857,3 +1265,394 @@ pub trait WriteBytesExt: io::Write {
 }
 
 impl<W: io::Write + ?Sized> WriteBytesExt for W {}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::Arc;
+    use model::{
+        Action,
+        Facility,
+        FacilityType,
+        Game,
+        Grid,
+        Player,
+        PlayerContext,
+        Rect,
+        TerrainType,
+        Vehicle,
+        VehicleType,
+        VehicleUpdate,
+        WeatherType,
+        World,
+    };
+    use protocol_error::ProtocolError;
+    use super::{Message, RemoteProcessClient};
+
+    fn encode(messages: &[Message]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        {
+            let mut client = RemoteProcessClient::new(io::empty(), &mut bytes);
+            for message in messages {
+                client.write_message(message).unwrap();
+            }
+        }
+        bytes
+    }
+
+    fn decode(bytes: &[u8], count: usize) -> Vec<Message> {
+        let mut client = RemoteProcessClient::new(bytes, io::sink());
+        (0..count).map(|_| client.read_message().unwrap()).collect()
+    }
+
+    fn assert_round_trip(messages: &[Message]) -> Vec<u8> {
+        let bytes = encode(messages);
+        let decoded = decode(&bytes, messages.len());
+        assert_eq!(decoded, messages);
+        assert_eq!(encode(&decoded), bytes);
+        bytes
+    }
+
+    fn game() -> Game {
+        Game {
+            random_seed: -1,
+            tick_count: 2,
+            world_width: 3.5,
+            world_height: 4.5,
+            fog_of_war_enabled: true,
+            victory_score: 6,
+            facility_capture_score: 7,
+            vehicle_elimination_score: 8,
+            action_detection_interval: 9,
+            base_action_count: 10,
+            additional_action_count_per_control_center: 11,
+            max_unit_group: 12,
+            terrain_weather_map_column_count: 13,
+            terrain_weather_map_row_count: 14,
+            plain_terrain_vision_factor: 15.5,
+            plain_terrain_stealth_factor: 16.5,
+            plain_terrain_speed_factor: 17.5,
+            swamp_terrain_vision_factor: 18.5,
+            swamp_terrain_stealth_factor: 19.5,
+            swamp_terrain_speed_factor: 20.5,
+            forest_terrain_vision_factor: 21.5,
+            forest_terrain_stealth_factor: 22.5,
+            forest_terrain_speed_factor: 23.5,
+            clear_weather_vision_factor: 24.5,
+            clear_weather_stealth_factor: 25.5,
+            clear_weather_speed_factor: 26.5,
+            cloud_weather_vision_factor: 27.5,
+            cloud_weather_stealth_factor: 28.5,
+            cloud_weather_speed_factor: 29.5,
+            rain_weather_vision_factor: 30.5,
+            rain_weather_stealth_factor: 31.5,
+            rain_weather_speed_factor: 32.5,
+            vehicle_radius: 33.5,
+            tank_durability: 34,
+            tank_speed: 35.5,
+            tank_vision_range: 36.5,
+            tank_ground_attack_range: 37.5,
+            tank_aerial_attack_range: 38.5,
+            tank_ground_damage: 39,
+            tank_aerial_damage: 40,
+            tank_ground_defence: 41,
+            tank_aerial_defence: 42,
+            tank_attack_cooldown_ticks: 43,
+            tank_production_cost: 44,
+            ifv_durability: 45,
+            ifv_speed: 46.5,
+            ifv_vision_range: 47.5,
+            ifv_ground_attack_range: 48.5,
+            ifv_aerial_attack_range: 49.5,
+            ifv_ground_damage: 50,
+            ifv_aerial_damage: 51,
+            ifv_ground_defence: 52,
+            ifv_aerial_defence: 53,
+            ifv_attack_cooldown_ticks: 54,
+            ifv_production_cost: 55,
+            arrv_durability: 56,
+            arrv_speed: 57.5,
+            arrv_vision_range: 58.5,
+            arrv_ground_defence: 59,
+            arrv_aerial_defence: 60,
+            arrv_production_cost: 61,
+            arrv_repair_range: 62.5,
+            arrv_repair_speed: 63.5,
+            helicopter_durability: 64,
+            helicopter_speed: 65.5,
+            helicopter_vision_range: 66.5,
+            helicopter_ground_attack_range: 67.5,
+            helicopter_aerial_attack_range: 68.5,
+            helicopter_ground_damage: 69,
+            helicopter_aerial_damage: 70,
+            helicopter_ground_defence: 71,
+            helicopter_aerial_defence: 72,
+            helicopter_attack_cooldown_ticks: 73,
+            helicopter_production_cost: 74,
+            fighter_durability: 75,
+            fighter_speed: 76.5,
+            fighter_vision_range: 77.5,
+            fighter_ground_attack_range: 78.5,
+            fighter_aerial_attack_range: 79.5,
+            fighter_ground_damage: 80,
+            fighter_aerial_damage: 81,
+            fighter_ground_defence: 82,
+            fighter_aerial_defence: 83,
+            fighter_attack_cooldown_ticks: 84,
+            fighter_production_cost: 85,
+            max_facility_capture_points: 86.5,
+            facility_capture_points_per_vehicle_per_tick: 87.5,
+            facility_width: 88.5,
+            facility_height: 89.5,
+            base_tactical_nuclear_strike_cooldown: 90,
+            tactical_nuclear_strike_cooldown_decrease_per_control_center: 91,
+            max_tactical_nuclear_strike_damage: 92.5,
+            tactical_nuclear_strike_radius: 93.5,
+            tactical_nuclear_strike_delay: 94,
+        }
+    }
+
+    fn player(id: i64, me: bool, score: i32) -> Player {
+        Player {
+            id,
+            me,
+            strategy_crashed: false,
+            score,
+            remaining_action_cooldown_ticks: 0,
+            remaining_nuclear_strike_cooldown_ticks: 0,
+            next_nuclear_strike_vehicle_id: -1,
+            next_nuclear_strike_tick_index: -1,
+            next_nuclear_strike_x: -1.0,
+            next_nuclear_strike_y: -1.0,
+        }
+    }
+
+    fn facility(id: i64, kind: FacilityType, owner_player_id: i64) -> Facility {
+        Facility {
+            id,
+            kind: Some(kind),
+            owner_player_id,
+            left: 32.0 * id as f64,
+            top: 64.0,
+            capture_points: 0.0,
+            vehicle_type: if kind == FacilityType::VehicleFactory { Some(VehicleType::Tank) } else { None },
+            production_progress: 0,
+        }
+    }
+
+    fn vehicle(id: i64, player_id: i64, kind: VehicleType) -> Vehicle {
+        Vehicle {
+            id,
+            x: 18.0 + id as f64,
+            y: 50.5,
+            radius: 2.0,
+            player_id,
+            durability: 100,
+            max_durability: 100,
+            max_speed: 0.4,
+            vision_range: 80.0,
+            squared_vision_range: 6400.0,
+            ground_attack_range: 20.0,
+            squared_ground_attack_range: 400.0,
+            aerial_attack_range: 18.0,
+            squared_aerial_attack_range: 324.0,
+            ground_damage: 100,
+            aerial_damage: 60,
+            ground_defence: 80,
+            aerial_defence: 60,
+            attack_cooldown_ticks: 60,
+            remaining_attack_cooldown_ticks: 0,
+            kind: Some(kind),
+            aerial: false,
+            selected: false,
+            groups: vec![1, 3],
+        }
+    }
+
+    fn world(tick_index: i32, players: Arc<[Player]>, facilities: Arc<[Facility]>) -> World {
+        World {
+            tick_index,
+            tick_count: 20000,
+            width: 1024.0,
+            height: 1024.0,
+            players,
+            new_vehicles: Vec::new(),
+            vehicle_updates: Vec::new(),
+            terrain_by_cell_x_y: Grid::from_columns(&[
+                vec![TerrainType::Plain, TerrainType::Swamp],
+                vec![TerrainType::Forest, TerrainType::Plain],
+            ]).unwrap(),
+            weather_by_cell_x_y: Grid::from_columns(&[
+                vec![WeatherType::Clear, WeatherType::Cloud],
+                vec![WeatherType::Rain, WeatherType::Clear],
+            ]).unwrap(),
+            facilities,
+        }
+    }
+
+    fn player_context(player: &Player, world: World) -> Message {
+        Message::PlayerContext(PlayerContext { player: player.clone(), world })
+    }
+
+    #[test]
+    fn round_trip_authentication_token() {
+        let bytes = assert_round_trip(&[Message::AuthenticationToken("0000000000000000".to_string())]);
+        assert_eq!(&bytes[..5], &[2, 16, 0, 0, 0]);
+    }
+
+    #[test]
+    fn round_trip_game() {
+        assert_round_trip(&[Message::GameContext(game())]);
+    }
+
+    #[test]
+    fn round_trip_action() {
+        assert_round_trip(&[
+            Message::ActionMessage(Action::default()),
+            Message::ActionMessage(Action::clear_and_select(Rect::new(0.0, 0.0, 512.0, 256.0), Some(VehicleType::Ifv))),
+            Message::ActionMessage(Action::select_group(7)),
+            Message::ActionMessage(Action::move_by(-10.5, 20.25, 0.3)),
+            Message::ActionMessage(Action::setup_production(3, None)),
+            Message::ActionMessage(Action::nuclear_strike(100.0, 200.0, 42)),
+        ]);
+    }
+
+    #[test]
+    fn round_trip_player_context() {
+        let me = player(1, true, 0);
+        let players: Arc<[Player]> = Arc::from(vec![me.clone(), player(2, false, 0)]);
+        let facilities: Arc<[Facility]> = Arc::from(vec![
+            facility(1, FacilityType::ControlCenter, -1),
+            facility(2, FacilityType::VehicleFactory, 1),
+        ]);
+        let mut first = world(0, players.clone(), facilities.clone());
+        first.new_vehicles = vec![vehicle(1, 1, VehicleType::Tank), vehicle(2, 2, VehicleType::Arrv)];
+        let mut second = world(1, players.clone(), facilities.clone());
+        second.vehicle_updates = vec![VehicleUpdate {
+            id: 1,
+            x: 20.0,
+            y: 50.5,
+            durability: 90,
+            remaining_attack_cooldown_ticks: 59,
+            selected: true,
+            groups: Vec::new(),
+        }];
+
+        assert_round_trip(&[
+            Message::GameContext(game()),
+            player_context(&me, first),
+            player_context(&me, second),
+            Message::GameOver,
+        ]);
+    }
+
+    #[test]
+    fn unchanged_players_and_facilities_are_sent_as_minus_one() {
+        let me = player(1, true, 0);
+        let players: Arc<[Player]> = Arc::from(vec![me.clone(), player(2, false, 0)]);
+        let facilities: Arc<[Facility]> = Arc::from(vec![facility(1, FacilityType::ControlCenter, -1)]);
+        let first = assert_round_trip(&[player_context(&me, world(0, players.clone(), facilities.clone()))]);
+        let both = assert_round_trip(&[
+            player_context(&me, world(0, players.clone(), facilities.clone())),
+            player_context(&me, world(1, Arc::from(players.to_vec()), Arc::from(facilities.to_vec()))),
+        ]);
+        let second = &both[first.len()..];
+
+        assert_eq!(second[2], 127);
+        assert_eq!(&second[3..11], &1i64.to_le_bytes());
+        assert_eq!(&second[36..40], &(-1i32).to_le_bytes());
+        assert_eq!(&second[second.len() - 4..], &(-1i32).to_le_bytes());
+    }
+
+    #[test]
+    fn unchanged_players_and_facilities_are_sent_by_id() {
+        let me = player(1, true, 0);
+        let facilities: Arc<[Facility]> = Arc::from(vec![
+            facility(1, FacilityType::ControlCenter, -1),
+            facility(2, FacilityType::VehicleFactory, 1),
+        ]);
+        let mut captured = facilities.to_vec();
+        captured[0].owner_player_id = 1;
+        captured[0].capture_points = 100.0;
+        let messages = [
+            player_context(&me, world(0, Arc::from(vec![me.clone(), player(2, false, 0)]), facilities.clone())),
+            player_context(&me, world(1, Arc::from(vec![me.clone(), player(2, false, 50)]), Arc::from(captured))),
+        ];
+        let first = assert_round_trip(&messages[..1]);
+        let both = assert_round_trip(&messages);
+        let second = &both[first.len()..];
+
+        const PLAYERS: usize = 1 + 1 + 9 + 1 + 4 + 4 + 8 + 8;
+        assert_eq!(&second[PLAYERS..PLAYERS + 4], &2i32.to_le_bytes());
+        assert_eq!(second[PLAYERS + 4], 127);
+        assert_eq!(&second[PLAYERS + 5..PLAYERS + 13], &1i64.to_le_bytes());
+        assert_eq!(second[PLAYERS + 13], 1);
+
+        const FACILITY: usize = 1 + 8 + 1 + 8 + 8 + 8 + 8 + 1 + 4;
+        let facilities = second.len() - 9 - FACILITY - 4;
+        assert_eq!(&second[facilities..facilities + 4], &2i32.to_le_bytes());
+        assert_eq!(second[second.len() - 9], 127);
+        assert_eq!(&second[second.len() - 8..], &2i64.to_le_bytes());
+    }
+
+    #[test]
+    fn truncated_stream_reports_field_path() {
+        let me = player(1, true, 0);
+        let mut value = world(0, Arc::from(vec![me.clone()]), Arc::from(vec![facility(1, FacilityType::ControlCenter, -1)]));
+        value.new_vehicles = vec![vehicle(1, 1, VehicleType::Tank)];
+        let bytes = encode(&[player_context(&me, value)]);
+        let truncated_at = |len: usize| {
+            match RemoteProcessClient::new(&bytes[..len], io::sink()).read_message() {
+                Err(ProtocolError::TruncatedStream { path }) => path,
+                v => panic!("unexpected result: {:?}", v),
+            }
+        };
+
+        assert_eq!(truncated_at(0), "Message");
+        assert_eq!(truncated_at(1), "PlayerContext");
+        assert_eq!(truncated_at(2), "PlayerContext::player");
+        assert_eq!(truncated_at(5), "Player::id");
+        assert_eq!(truncated_at(53), "World");
+        assert_eq!(truncated_at(55), "World::tick_index");
+        assert_eq!(truncated_at(65), "World::width");
+        assert_eq!(truncated_at(80), "World::players");
+        assert_eq!(truncated_at(bytes.len() - 218), "Vehicle::x");
+        assert_eq!(truncated_at(bytes.len() - 90), "Vehicle::groups");
+        assert_eq!(truncated_at(bytes.len() - 85), "World::vehicle_updates");
+        assert_eq!(truncated_at(bytes.len() - 75), "World::terrain_by_cell_x_y");
+        assert_eq!(truncated_at(bytes.len() - 60), "World::weather_by_cell_x_y");
+        assert_eq!(truncated_at(bytes.len() - 50), "World::facilities");
+        assert_eq!(truncated_at(bytes.len() - 20), "Facility::top");
+        assert_eq!(truncated_at(bytes.len() - 1), "Facility::production_progress");
+    }
+
+    #[test]
+    fn truncated_game_reports_field_path() {
+        let bytes = encode(&[Message::GameContext(game())]);
+        let truncated_at = |len: usize| {
+            match RemoteProcessClient::new(&bytes[..len], io::sink()).read_message() {
+                Err(ProtocolError::TruncatedStream { path }) => path,
+                v => panic!("unexpected result: {:?}", v),
+            }
+        };
+
+        assert_eq!(truncated_at(1), "Game");
+        assert_eq!(truncated_at(11), "Game::tick_count");
+        assert_eq!(truncated_at(bytes.len() - 1), "Game::tactical_nuclear_strike_delay");
+    }
+
+    #[test]
+    fn oversized_length_is_an_error() {
+        let me = player(1, true, 0);
+        let mut bytes = encode(&[player_context(&me, world(0, Arc::from(vec![me.clone()]), Arc::from(Vec::new())))]);
+        const NEW_VEHICLES: usize = 1 + 1 + 51 + 1 + 4 + 4 + 8 + 8 + 4 + 9;
+        assert_eq!(&bytes[NEW_VEHICLES..NEW_VEHICLES + 4], &0i32.to_le_bytes());
+        bytes[NEW_VEHICLES..NEW_VEHICLES + 4].copy_from_slice(&i32::MAX.to_le_bytes());
+        bytes.truncate(NEW_VEHICLES + 4);
+
+        match RemoteProcessClient::new(&bytes[..], io::sink()).read_message() {
+            Err(ProtocolError::TruncatedStream { path }) => assert_eq!(path, "Vehicle"),
+            v => panic!("unexpected result: {:?}", v),
+        }
+    }
+}
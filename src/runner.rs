@@ -1,87 +1,221 @@
-extern crate core;
-
-mod model;
-mod my_strategy;
-mod remote_process_client;
-mod strategy;
-
+use std::fmt;
 use std::io;
+use std::net::TcpStream;
+use std::str::FromStr;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Mutex;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use action_validator;
+use model::{Action, Game, PlayerContext};
+use protocol_error::ProtocolResult;
 use remote_process_client::RemoteProcessClient;
 use strategy::Strategy;
+use world_state::WorldState;
 
-struct Args {
-    host: String,
-    port: u16,
-    token: String,
+#[derive(Clone, Debug, Default)]
+pub struct RunnerConfig {
+    pub tick_time_budget: Option<Duration>,
+    pub total_time_budget: Option<Duration>,
+    pub fallback_deadline: Option<Duration>,
+    pub restart_strategy_after_panics: Option<u32>,
+    pub action_validation: ValidationMode,
 }
 
-fn main() {
-    use std::io::{stderr, Write};
-    use std::process::exit;
-    use my_strategy::MyStrategy;
-
-    let args = parse_args();
-
-    let client = match RemoteProcessClient::connect(&args.host[..], args.port) {
-        Ok(v) => v,
-        Err(v) => {
-            write!(&mut stderr(), "{:?}\n", v).unwrap();
-            exit(-1);
-        }
-    };
-
-    let mut runner = Runner::new(client, args.token);
-
-    match runner.run::<MyStrategy>() {
-        Ok(_) => (),
-        Err(v) => {
-            write!(&mut stderr(), "{:?}\n", v).unwrap();
-            exit(-1);
-        }
-    }
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ValidationMode {
+    #[default]
+    Off,
+    Warn,
+    Strict,
 }
 
-fn parse_args() -> Args {
-    if std::env::args().count() == 4 {
-        Args {
-            host: std::env::args().nth(1).unwrap(),
-            port: std::env::args().nth(2).unwrap().parse().expect("Cant't parse port"),
-            token: std::env::args().nth(3).unwrap(),
-        }
-    } else {
-        Args {
-            host: "127.0.0.1".to_string(),
-            port: 31001,
-            token: "0000000000000000".to_string(),
+impl FromStr for ValidationMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "off" => Ok(ValidationMode::Off),
+            "warn" => Ok(ValidationMode::Warn),
+            "strict" => Ok(ValidationMode::Strict),
+            v => Err(format!("unknown validation mode: {}", v)),
         }
     }
 }
 
-struct Runner {
-    client: RemoteProcessClient,
+pub struct Runner<R = TcpStream, W: io::Write = TcpStream> {
+    client: RemoteProcessClient<R, W>,
     token: String,
+    config: RunnerConfig,
+    total_time: Duration,
+    consecutive_panics: u32,
+    log: Box<dyn io::Write>,
+    world_state: WorldState,
 }
 
-impl Runner {
-    pub fn new(client: RemoteProcessClient, token: String,) -> Self {
-        Runner { client, token }
+impl<R: io::Read + Send, W: io::Write + Send> Runner<R, W> {
+    pub fn new(client: RemoteProcessClient<R, W>, token: String) -> Self {
+        Runner::with_config(client, token, RunnerConfig::default())
     }
 
-    pub fn run<T: Strategy>(&mut self) -> io::Result<()> {
-        use model::Action;
+    pub fn with_config(client: RemoteProcessClient<R, W>, token: String, config: RunnerConfig) -> Self {
+        Runner {
+            client,
+            token,
+            config,
+            total_time: Duration::default(),
+            consecutive_panics: 0,
+            log: Box::new(io::stderr()),
+            world_state: WorldState::new(),
+        }
+    }
+
+    pub fn set_log<L: io::Write + 'static>(&mut self, log: L) {
+        self.log = Box::new(log);
+    }
 
+    pub fn run<T: Strategy>(&mut self) -> ProtocolResult<()> {
         self.client.write_authentication_token_message(self.token.clone())?;
         self.client.write_protocol_version_message()?;
         self.client.read_team_size_message()?;
         let game = self.client.read_game_message()?;
         let mut strategy = T::default();
+        self.start(&mut strategy, &game);
+        let mut last_world = None;
 
         while let Some(player_context) = self.client.read_player_context_message()? {
-            let mut action = Action::default();
-            strategy.act(&player_context.player, &player_context.world, &game, &mut action);
-            self.client.write_action_message(action)?;
+            self.act(&mut strategy, &player_context, &game)?;
+            last_world = Some(player_context.world);
+        }
+
+        if let Some(message) = catch_panic(|| strategy.on_game_over(last_world.as_ref(), &game)) {
+            warn(&mut self.log, format_args!("strategy panicked on game over: {}", message));
         }
 
         Ok(())
     }
+
+    fn start<T: Strategy>(&mut self, strategy: &mut T, game: &Game) {
+        if let Some(message) = catch_panic(|| strategy.on_start(game)) {
+            warn(&mut self.log, format_args!("strategy panicked on start: {}", message));
+        }
+    }
+
+    fn act<T: Strategy>(&mut self, strategy: &mut T, context: &PlayerContext, game: &Game) -> ProtocolResult<()> {
+        let mut action = Action::default();
+        if self.config.action_validation != ValidationMode::Off {
+            self.world_state.apply(&context.world);
+        }
+        let started = Instant::now();
+
+        let (fallback_sent, panic) = match self.config.fallback_deadline {
+            Some(deadline) => {
+                let client = Mutex::new(&mut self.client);
+                let (done_sender, done_receiver) = mpsc::channel();
+                thread::scope(|scope| {
+                    let client = &client;
+                    let watchdog = scope.spawn(move || -> io::Result<bool> {
+                        match done_receiver.recv_timeout(deadline) {
+                            Err(RecvTimeoutError::Timeout) => {
+                                client.lock().unwrap().write_action_message(Action::default())?;
+                                Ok(true)
+                            }
+                            _ => Ok(false),
+                        }
+                    });
+                    let panic = call_strategy(strategy, context, game, &mut action);
+                    let _ = done_sender.send(());
+                    watchdog.join().unwrap().map(|fallback_sent| (fallback_sent, panic))
+                })?
+            }
+            None => (false, call_strategy(strategy, context, game, &mut action)),
+        };
+
+        self.account_time(context.world.tick_index, started.elapsed());
+
+        if let Some(message) = panic {
+            action = Action::default();
+            self.handle_panic(strategy, context.world.tick_index, game, &message);
+        } else {
+            self.consecutive_panics = 0;
+        }
+
+        if !fallback_sent && self.config.action_validation != ValidationMode::Off {
+            if let Err(error) = action_validator::validate(&action, &self.world_state, &context.world, game) {
+                if self.config.action_validation == ValidationMode::Strict {
+                    warn(&mut self.log, format_args!("tick {}: rejected invalid action {:?}: {}",
+                                                     context.world.tick_index, action.action, error));
+                    action = Action::default();
+                } else {
+                    warn(&mut self.log, format_args!("tick {}: sending invalid action {:?}: {}",
+                                                     context.world.tick_index, action.action, error));
+                }
+            }
+        }
+
+        if fallback_sent {
+            warn(&mut self.log, format_args!("tick {}: strategy missed the deadline, sent an empty action and dropped {:?}",
+                                             context.world.tick_index, action.action));
+            Ok(())
+        } else {
+            Ok(self.client.write_action_message(action)?)
+        }
+    }
+
+    fn handle_panic<T: Strategy>(&mut self, strategy: &mut T, tick_index: i32, game: &Game, message: &str) {
+        self.consecutive_panics += 1;
+        warn(&mut self.log, format_args!("tick {}: strategy panicked: {}", tick_index, message));
+
+        if let Some(limit) = self.config.restart_strategy_after_panics {
+            if self.consecutive_panics >= limit {
+                warn(&mut self.log, format_args!("tick {}: strategy panicked {} times in a row, restarting it",
+                                                 tick_index, self.consecutive_panics));
+                *strategy = T::default();
+                self.start(strategy, game);
+                self.consecutive_panics = 0;
+            }
+        }
+    }
+
+    fn account_time(&mut self, tick_index: i32, elapsed: Duration) {
+        let previous_total_time = self.total_time;
+        self.total_time += elapsed;
+
+        if let Some(budget) = self.config.tick_time_budget {
+            if elapsed > budget {
+                warn(&mut self.log, format_args!("tick {}: strategy took {:?}, tick budget is {:?}",
+                                                 tick_index, elapsed, budget));
+            }
+        }
+
+        if let Some(budget) = self.config.total_time_budget {
+            if previous_total_time <= budget && self.total_time > budget {
+                warn(&mut self.log, format_args!("tick {}: strategy took {:?} in total, total budget is {:?}",
+                                                 tick_index, self.total_time, budget));
+            }
+        }
+    }
+}
+
+fn call_strategy<T: Strategy>(strategy: &mut T, context: &PlayerContext, game: &Game, action: &mut Action)
+                              -> Option<String> {
+    catch_panic(|| strategy.act(&context.player, &context.world, game, action))
+}
+
+fn catch_panic<F: FnOnce()>(f: F) -> Option<String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).err().map(|payload| {
+        if let Some(v) = payload.downcast_ref::<&str>() {
+            v.to_string()
+        } else if let Some(v) = payload.downcast_ref::<String>() {
+            v.clone()
+        } else {
+            "unknown panic payload".to_string()
+        }
+    })
+}
+
+fn warn(log: &mut dyn io::Write, message: fmt::Arguments) {
+    let _ = writeln!(log, "{}", message);
+    let _ = log.flush();
 }
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use model::{Game, Rect, Unit, World};
+
+#[derive(Clone, Debug)]
+pub struct SpatialIndex {
+    cell_size: f64,
+    column_count: usize,
+    row_count: usize,
+    cells: Vec<Vec<i64>>,
+    positions: HashMap<i64, Position>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Position {
+    x: f64,
+    y: f64,
+    cell: usize,
+}
+
+impl SpatialIndex {
+    pub fn new(width: f64, height: f64, cell_size: f64) -> Self {
+        assert!(cell_size > 0.0, "SpatialIndex cell size must be positive: {}", cell_size);
+        let column_count = ((width / cell_size).ceil() as usize).max(1);
+        let row_count = ((height / cell_size).ceil() as usize).max(1);
+        SpatialIndex {
+            cell_size,
+            column_count,
+            row_count,
+            cells: vec![Vec::new(); column_count * row_count],
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn for_game(game: &Game) -> Self {
+        SpatialIndex::new(game.world_width, game.world_height, game.vehicle_radius * 16.0)
+    }
+
+    pub fn from_units<'a, T, I>(width: f64, height: f64, cell_size: f64, units: I) -> Self
+        where T: Unit + 'a, I: IntoIterator<Item=&'a T> {
+        let mut result = SpatialIndex::new(width, height, cell_size);
+        for unit in units {
+            result.update_unit(unit);
+        }
+        result
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn contains(&self, id: i64) -> bool {
+        self.positions.contains_key(&id)
+    }
+
+    pub fn position(&self, id: i64) -> Option<(f64, f64)> {
+        self.positions.get(&id).map(|v| (v.x, v.y))
+    }
+
+    pub fn update(&mut self, id: i64, x: f64, y: f64) {
+        let cell = self.cell_index(x, y);
+        if let Some(position) = self.positions.get_mut(&id) {
+            let old_cell = position.cell;
+            *position = Position { x, y, cell };
+            if old_cell == cell {
+                return;
+            }
+            remove_id(&mut self.cells[old_cell], id);
+        } else {
+            self.positions.insert(id, Position { x, y, cell });
+        }
+        self.cells[cell].push(id);
+    }
+
+    pub fn update_unit<T: Unit>(&mut self, unit: &T) {
+        self.update(unit.id(), unit.x(), unit.y());
+    }
+
+    pub fn remove(&mut self, id: i64) -> bool {
+        match self.positions.remove(&id) {
+            Some(position) => {
+                remove_id(&mut self.cells[position.cell], id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn apply(&mut self, world: &World) {
+        for vehicle in world.new_vehicles.iter() {
+            if vehicle.durability > 0 {
+                self.update_unit(vehicle);
+            }
+        }
+
+        for update in world.vehicle_updates.iter() {
+            if update.durability <= 0 {
+                self.remove(update.id);
+            } else if self.contains(update.id) {
+                self.update(update.id, update.x, update.y);
+            }
+        }
+    }
+
+    pub fn in_rect(&self, rect: &Rect) -> Vec<i64> {
+        let (min_column, min_row) = self.cell_x_y(rect.left, rect.top);
+        let (max_column, max_row) = self.cell_x_y(rect.right, rect.bottom);
+        let mut result = Vec::new();
+
+        for row in min_row..(max_row + 1) {
+            for column in min_column..(max_column + 1) {
+                for &id in self.cells[row * self.column_count + column].iter() {
+                    let position = &self.positions[&id];
+                    if rect.contains(position.x, position.y) {
+                        result.push(id);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    pub fn within_radius(&self, x: f64, y: f64, radius: f64) -> Vec<i64> {
+        let squared_radius = radius * radius;
+        let mut result = self.in_rect(&Rect::around(x, y, radius));
+        result.retain(|id| squared_distance(&self.positions[id], x, y) <= squared_radius);
+        result
+    }
+
+    pub fn nearest(&self, x: f64, y: f64, count: usize) -> Vec<i64> {
+        if count == 0 || self.is_empty() {
+            return Vec::new();
+        }
+
+        let (column, row) = self.cell_x_y(x, y);
+        let max_ring = self.column_count.max(self.row_count);
+        let mut candidates: Vec<(f64, i64)> = Vec::new();
+
+        for ring in 0..(max_ring + 1) {
+            self.visit_ring(column, row, ring, |id, position| {
+                candidates.push((squared_distance(position, x, y), id));
+            });
+
+            if candidates.len() >= count {
+                candidates.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+                candidates.truncate(count);
+                let reached = ring as f64 * self.cell_size;
+                if candidates[count - 1].0 <= reached * reached {
+                    break;
+                }
+            }
+        }
+
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        candidates.into_iter().take(count).map(|v| v.1).collect()
+    }
+
+    fn visit_ring<F: FnMut(i64, &Position)>(&self, column: usize, row: usize, ring: usize, mut f: F) {
+        let (column, row, ring) = (column as isize, row as isize, ring as isize);
+
+        for y in (row - ring)..(row + ring + 1) {
+            if y < 0 || y >= self.row_count as isize {
+                continue;
+            }
+            let step = if y == row - ring || y == row + ring { 1 } else { (2 * ring).max(1) };
+            let mut x = column - ring;
+            while x <= column + ring {
+                if x >= 0 && x < self.column_count as isize {
+                    for &id in self.cells[y as usize * self.column_count + x as usize].iter() {
+                        f(id, &self.positions[&id]);
+                    }
+                }
+                x += step;
+            }
+        }
+    }
+
+    fn cell_x_y(&self, x: f64, y: f64) -> (usize, usize) {
+        (clamp_cell(x / self.cell_size, self.column_count), clamp_cell(y / self.cell_size, self.row_count))
+    }
+
+    fn cell_index(&self, x: f64, y: f64) -> usize {
+        let (column, row) = self.cell_x_y(x, y);
+        row * self.column_count + column
+    }
+}
+
+fn clamp_cell(value: f64, count: usize) -> usize {
+    if value <= 0.0 || value.is_nan() {
+        0
+    } else {
+        (value as usize).min(count - 1)
+    }
+}
+
+fn squared_distance(position: &Position, x: f64, y: f64) -> f64 {
+    let dx = position.x - x;
+    let dy = position.y - y;
+    dx * dx + dy * dy
+}
+
+fn remove_id(cell: &mut Vec<i64>, id: i64) {
+    if let Some(index) = cell.iter().position(|&v| v == id) {
+        cell.swap_remove(index);
+    }
+}
@@ -1,5 +1,9 @@
 use model::{Game, Action, Player, World};
 
 pub trait Strategy: Default {
+    fn on_start(&mut self, _game: &Game) {}
+
     fn act(&mut self, me: &Player, world: &World, game: &Game, action: &mut Action);
+
+    fn on_game_over(&mut self, _last_world: Option<&World>, _game: &Game) {}
 }
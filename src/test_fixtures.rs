@@ -0,0 +1,177 @@
+use std::sync::Arc;
+use model::{Facility, Game, Grid, Player, Vehicle, VehicleType, VehicleUpdate, World};
+
+pub fn game() -> Game {
+    Game {
+        random_seed: 0,
+        tick_count: 20000,
+        world_width: 1024.0,
+        world_height: 1024.0,
+        fog_of_war_enabled: false,
+        victory_score: 0,
+        facility_capture_score: 100,
+        vehicle_elimination_score: 1,
+        action_detection_interval: 60,
+        base_action_count: 12,
+        additional_action_count_per_control_center: 3,
+        max_unit_group: 100,
+        terrain_weather_map_column_count: 32,
+        terrain_weather_map_row_count: 32,
+        plain_terrain_vision_factor: 1.0,
+        plain_terrain_stealth_factor: 1.0,
+        plain_terrain_speed_factor: 1.0,
+        swamp_terrain_vision_factor: 1.0,
+        swamp_terrain_stealth_factor: 1.0,
+        swamp_terrain_speed_factor: 0.6,
+        forest_terrain_vision_factor: 0.8,
+        forest_terrain_stealth_factor: 0.6,
+        forest_terrain_speed_factor: 0.8,
+        clear_weather_vision_factor: 1.0,
+        clear_weather_stealth_factor: 1.0,
+        clear_weather_speed_factor: 1.0,
+        cloud_weather_vision_factor: 0.8,
+        cloud_weather_stealth_factor: 0.8,
+        cloud_weather_speed_factor: 0.8,
+        rain_weather_vision_factor: 0.6,
+        rain_weather_stealth_factor: 0.6,
+        rain_weather_speed_factor: 0.6,
+        vehicle_radius: 2.0,
+        tank_durability: 100,
+        tank_speed: 0.3,
+        tank_vision_range: 80.0,
+        tank_ground_attack_range: 20.0,
+        tank_aerial_attack_range: 18.0,
+        tank_ground_damage: 100,
+        tank_aerial_damage: 60,
+        tank_ground_defence: 80,
+        tank_aerial_defence: 60,
+        tank_attack_cooldown_ticks: 60,
+        tank_production_cost: 60,
+        ifv_durability: 100,
+        ifv_speed: 0.4,
+        ifv_vision_range: 80.0,
+        ifv_ground_attack_range: 18.0,
+        ifv_aerial_attack_range: 20.0,
+        ifv_ground_damage: 90,
+        ifv_aerial_damage: 80,
+        ifv_ground_defence: 60,
+        ifv_aerial_defence: 80,
+        ifv_attack_cooldown_ticks: 60,
+        ifv_production_cost: 60,
+        arrv_durability: 100,
+        arrv_speed: 0.4,
+        arrv_vision_range: 60.0,
+        arrv_ground_defence: 50,
+        arrv_aerial_defence: 20,
+        arrv_production_cost: 60,
+        arrv_repair_range: 10.0,
+        arrv_repair_speed: 0.05,
+        helicopter_durability: 100,
+        helicopter_speed: 0.9,
+        helicopter_vision_range: 100.0,
+        helicopter_ground_attack_range: 20.0,
+        helicopter_aerial_attack_range: 18.0,
+        helicopter_ground_damage: 100,
+        helicopter_aerial_damage: 80,
+        helicopter_ground_defence: 40,
+        helicopter_aerial_defence: 40,
+        helicopter_attack_cooldown_ticks: 60,
+        helicopter_production_cost: 60,
+        fighter_durability: 70,
+        fighter_speed: 1.2,
+        fighter_vision_range: 120.0,
+        fighter_ground_attack_range: 0.0,
+        fighter_aerial_attack_range: 20.0,
+        fighter_ground_damage: 0,
+        fighter_aerial_damage: 100,
+        fighter_ground_defence: 70,
+        fighter_aerial_defence: 70,
+        fighter_attack_cooldown_ticks: 60,
+        fighter_production_cost: 60,
+        max_facility_capture_points: 100.0,
+        facility_capture_points_per_vehicle_per_tick: 0.005,
+        facility_width: 64.0,
+        facility_height: 64.0,
+        base_tactical_nuclear_strike_cooldown: 1200,
+        tactical_nuclear_strike_cooldown_decrease_per_control_center: 60,
+        max_tactical_nuclear_strike_damage: 99.0,
+        tactical_nuclear_strike_radius: 50.0,
+        tactical_nuclear_strike_delay: 30,
+    }
+}
+
+pub fn player(id: i64, me: bool) -> Player {
+    Player {
+        id,
+        me,
+        strategy_crashed: false,
+        score: 0,
+        remaining_action_cooldown_ticks: 0,
+        remaining_nuclear_strike_cooldown_ticks: 0,
+        next_nuclear_strike_vehicle_id: -1,
+        next_nuclear_strike_tick_index: -1,
+        next_nuclear_strike_x: -1.0,
+        next_nuclear_strike_y: -1.0,
+    }
+}
+
+pub fn players() -> Vec<Player> {
+    vec![player(1, true), player(2, false)]
+}
+
+pub fn vehicle(id: i64, player_id: i64, x: f64, y: f64) -> Vehicle {
+    Vehicle {
+        id,
+        x,
+        y,
+        radius: 2.0,
+        player_id,
+        durability: 100,
+        max_durability: 100,
+        max_speed: 0.3,
+        vision_range: 80.0,
+        squared_vision_range: 6400.0,
+        ground_attack_range: 20.0,
+        squared_ground_attack_range: 400.0,
+        aerial_attack_range: 18.0,
+        squared_aerial_attack_range: 324.0,
+        ground_damage: 100,
+        aerial_damage: 60,
+        ground_defence: 80,
+        aerial_defence: 60,
+        attack_cooldown_ticks: 60,
+        remaining_attack_cooldown_ticks: 0,
+        kind: Some(VehicleType::Tank),
+        aerial: false,
+        selected: false,
+        groups: Vec::new(),
+    }
+}
+
+pub fn update(vehicle: &Vehicle, x: f64, y: f64, durability: i32, groups: Vec<i32>) -> VehicleUpdate {
+    VehicleUpdate {
+        id: vehicle.id,
+        x,
+        y,
+        durability,
+        remaining_attack_cooldown_ticks: 0,
+        selected: vehicle.selected,
+        groups,
+    }
+}
+
+pub fn world(tick_index: i32, players: Vec<Player>, new_vehicles: Vec<Vehicle>,
+             vehicle_updates: Vec<VehicleUpdate>) -> World {
+    World {
+        tick_index,
+        tick_count: 20000,
+        width: 1024.0,
+        height: 1024.0,
+        players: Arc::from(players),
+        new_vehicles,
+        vehicle_updates,
+        terrain_by_cell_x_y: Grid::default(),
+        weather_by_cell_x_y: Grid::default(),
+        facilities: Arc::from(Vec::<Facility>::new()),
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use model::{Game, Vehicle, World};
+use visibility::Visibility;
+use world_state::WorldState;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Velocity {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Velocity {
+    pub fn new(x: f64, y: f64) -> Self {
+        Velocity { x, y }
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    pub fn heading(&self) -> f64 {
+        self.y.atan2(self.x)
+    }
+
+    fn capped(self, max_speed: f64) -> Self {
+        let speed = self.speed();
+        if speed > max_speed && speed > 0.0 {
+            Velocity::new(self.x * max_speed / speed, self.y * max_speed / speed)
+        } else {
+            self
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Sample {
+    x: f64,
+    y: f64,
+    tick_index: i32,
+    velocity: Velocity,
+}
+
+#[derive(Clone, Debug)]
+pub struct VelocityTracker {
+    smoothing: f64,
+    samples: HashMap<i64, Sample>,
+}
+
+impl VelocityTracker {
+    pub fn new(smoothing: f64) -> Self {
+        VelocityTracker { smoothing: smoothing.clamp(0.0, 1.0), samples: HashMap::new() }
+    }
+
+    pub fn apply(&mut self, state: &WorldState, world: &World, game: &Game) {
+        let visibility = Visibility::new(state, world, game);
+
+        self.samples.retain(|id, _| state.vehicle(*id).is_some());
+
+        for vehicle in state.vehicles() {
+            if !visibility.is_reported(vehicle.id) && !visibility.can_see(vehicle) {
+                continue;
+            }
+
+            let max_speed = vehicle.max_speed * world.vehicle_factors(game, vehicle).speed;
+            let smoothing = self.smoothing;
+
+            match self.samples.get_mut(&vehicle.id) {
+                Some(sample) => {
+                    let ticks = world.tick_index - sample.tick_index;
+                    if ticks <= 0 {
+                        continue;
+                    }
+                    let measured = Velocity::new((vehicle.x - sample.x) / ticks as f64,
+                                                 (vehicle.y - sample.y) / ticks as f64).capped(max_speed);
+                    sample.velocity = Velocity::new(
+                        sample.velocity.x + (measured.x - sample.velocity.x) * smoothing,
+                        sample.velocity.y + (measured.y - sample.velocity.y) * smoothing,
+                    ).capped(max_speed);
+                    sample.x = vehicle.x;
+                    sample.y = vehicle.y;
+                    sample.tick_index = world.tick_index;
+                }
+                None => {
+                    self.samples.insert(vehicle.id, Sample {
+                        x: vehicle.x,
+                        y: vehicle.y,
+                        tick_index: world.tick_index,
+                        velocity: Velocity::default(),
+                    });
+                }
+            }
+        }
+    }
+
+    pub fn velocity(&self, id: i64) -> Option<Velocity> {
+        self.samples.get(&id).map(|v| v.velocity)
+    }
+
+    pub fn group_velocity<I: IntoIterator<Item=i64>>(&self, ids: I) -> Velocity {
+        let mut sum = Velocity::default();
+        let mut count = 0;
+        for velocity in ids.into_iter().filter_map(|id| self.velocity(id)) {
+            sum.x += velocity.x;
+            sum.y += velocity.y;
+            count += 1;
+        }
+        if count > 0 {
+            Velocity::new(sum.x / count as f64, sum.y / count as f64)
+        } else {
+            sum
+        }
+    }
+
+    pub fn predict(&self, vehicle: &Vehicle, ticks: i32) -> (f64, f64) {
+        let velocity = self.velocity(vehicle.id).unwrap_or_default();
+        (vehicle.x + velocity.x * ticks as f64, vehicle.y + velocity.y * ticks as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_fixtures::{game, players, update, vehicle, world};
+    use world_state::WorldState;
+    use super::VelocityTracker;
+
+    #[test]
+    fn visible_vehicle_without_updates_decays_to_zero_velocity() {
+        let mut game = game();
+        game.fog_of_war_enabled = true;
+        let mut state = WorldState::new();
+        let mut tracker = VelocityTracker::new(0.5);
+        let enemy = vehicle(2, 2, 140.0, 100.0);
+        let current = world(0, players(), vec![vehicle(1, 1, 100.0, 100.0), enemy.clone()], Vec::new());
+        state.apply(&current);
+        tracker.apply(&state, &current, &game);
+
+        for tick_index in 1..4 {
+            let x = 140.0 + 0.3 * tick_index as f64;
+            let current = world(tick_index, players(), Vec::new(), vec![update(&enemy, x, 100.0, 100, Vec::new())]);
+            state.apply(&current);
+            tracker.apply(&state, &current, &game);
+        }
+        assert!(tracker.velocity(2).unwrap().x > 0.2);
+
+        for tick_index in 4..64 {
+            let current = world(tick_index, players(), Vec::new(), Vec::new());
+            state.apply(&current);
+            tracker.apply(&state, &current, &game);
+        }
+        assert!(tracker.velocity(2).unwrap().speed() < 1e-9);
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashSet;
+use model::{Game, Unit, Vehicle, World};
+use world_state::WorldState;
+
+#[derive(Clone, Debug)]
+pub struct Visibility<'a> {
+    fog_of_war_enabled: bool,
+    my_player_id: Option<i64>,
+    observers: Vec<(&'a Vehicle, f64)>,
+    reported: HashSet<i64>,
+    world: &'a World,
+    game: &'a Game,
+}
+
+impl<'a> Visibility<'a> {
+    pub fn new(state: &'a WorldState, world: &'a World, game: &'a Game) -> Self {
+        Visibility {
+            fog_of_war_enabled: game.fog_of_war_enabled,
+            my_player_id: state.my_player_id(),
+            observers: state.mine()
+                .map(|v| (v, v.vision_range * world.vehicle_factors(game, v).vision))
+                .collect(),
+            reported: world.new_vehicles.iter().map(|v| v.id)
+                .chain(world.vehicle_updates.iter().map(|v| v.id))
+                .collect(),
+            world,
+            game,
+        }
+    }
+
+    pub fn is_reported(&self, id: i64) -> bool {
+        self.reported.contains(&id)
+    }
+
+    pub fn is_mine(&self, vehicle: &Vehicle) -> bool {
+        Some(vehicle.player_id) == self.my_player_id
+    }
+
+    pub fn can_see(&self, vehicle: &Vehicle) -> bool {
+        self.sees_with_margin(vehicle, |_| 0.0)
+    }
+
+    pub fn confirms_destruction(&self, vehicle: &Vehicle) -> bool {
+        self.sees_with_margin(vehicle, |observer| observer.max_speed + vehicle.max_speed + vehicle.radius)
+    }
+
+    fn sees_with_margin<F: Fn(&Vehicle) -> f64>(&self, vehicle: &Vehicle, margin: F) -> bool {
+        if !self.fog_of_war_enabled || self.is_mine(vehicle) {
+            return true;
+        }
+        let stealth = self.world.vehicle_factors(self.game, vehicle).stealth;
+        self.observers.iter().any(|&(observer, vision_range)| {
+            let range = vision_range * stealth - margin(observer);
+            range >= 0.0 && observer.squared_distance_to_unit(vehicle) <= range * range
+        })
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use model::{Facility, Player, Rect, Vehicle, VehicleType, World};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Extent {
+    pub centroid_x: f64,
+    pub centroid_y: f64,
+    pub bounds: Rect,
+}
+
+#[derive(Clone, Debug)]
+pub struct WorldState {
+    tick_index: i32,
+    my_player_id: Option<i64>,
+    players: Arc<[Player]>,
+    facilities: Arc<[Facility]>,
+    vehicles: BTreeMap<i64, Vehicle>,
+}
+
+impl WorldState {
+    pub fn new() -> Self {
+        WorldState {
+            tick_index: -1,
+            my_player_id: None,
+            players: Arc::from(Vec::new()),
+            facilities: Arc::from(Vec::new()),
+            vehicles: BTreeMap::new(),
+        }
+    }
+
+    pub fn apply(&mut self, world: &World) {
+        self.tick_index = world.tick_index;
+        self.my_player_id = world.get_my_player().map(|v| v.id).or(self.my_player_id);
+        self.players = world.players.clone();
+        self.facilities = world.facilities.clone();
+
+        for vehicle in world.new_vehicles.iter() {
+            if vehicle.durability > 0 {
+                self.vehicles.insert(vehicle.id, vehicle.clone());
+            }
+        }
+
+        for update in world.vehicle_updates.iter() {
+            if update.durability <= 0 {
+                self.vehicles.remove(&update.id);
+            } else if let Some(vehicle) = self.vehicles.get_mut(&update.id) {
+                vehicle.update(update);
+            }
+        }
+    }
+
+    pub fn tick_index(&self) -> i32 {
+        self.tick_index
+    }
+
+    pub fn my_player_id(&self) -> Option<i64> {
+        self.my_player_id
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    pub fn my_player(&self) -> Option<&Player> {
+        self.players.iter().find(|v| v.me)
+    }
+
+    pub fn opponent_player(&self) -> Option<&Player> {
+        self.players.iter().find(|v| !v.me)
+    }
+
+    pub fn facilities(&self) -> &[Facility] {
+        &self.facilities
+    }
+
+    pub fn vehicle(&self, id: i64) -> Option<&Vehicle> {
+        self.vehicles.get(&id)
+    }
+
+    pub fn vehicles_count(&self) -> usize {
+        self.vehicles.len()
+    }
+
+    pub fn vehicles(&self) -> impl Iterator<Item=&Vehicle> {
+        self.vehicles.values()
+    }
+
+    pub fn mine(&self) -> impl Iterator<Item=&Vehicle> {
+        let my_player_id = self.my_player_id;
+        self.vehicles().filter(move |v| Some(v.player_id) == my_player_id)
+    }
+
+    pub fn enemy(&self) -> impl Iterator<Item=&Vehicle> {
+        let my_player_id = self.my_player_id;
+        self.vehicles().filter(move |v| Some(v.player_id) != my_player_id)
+    }
+
+    pub fn by_type(&self, kind: VehicleType) -> impl Iterator<Item=&Vehicle> {
+        self.vehicles().filter(move |v| v.kind == Some(kind))
+    }
+
+    pub fn selected(&self) -> impl Iterator<Item=&Vehicle> {
+        self.mine().filter(|v| v.selected)
+    }
+
+    pub fn in_group(&self, group: i32) -> impl Iterator<Item=&Vehicle> {
+        self.mine().filter(move |v| v.groups.contains(&group))
+    }
+
+    pub fn group_extent(&self, group: i32) -> Option<Extent> {
+        extent(self.in_group(group))
+    }
+}
+
+pub fn extent<'a, I: IntoIterator<Item=&'a Vehicle>>(vehicles: I) -> Option<Extent> {
+    let (mut sum_x, mut sum_y, mut count) = (0.0, 0.0, 0);
+    let mut bounds: Option<Rect> = None;
+    for vehicle in vehicles {
+        sum_x += vehicle.x;
+        sum_y += vehicle.y;
+        count += 1;
+        bounds = Some(match bounds {
+            Some(v) => Rect::new(v.left.min(vehicle.x), v.top.min(vehicle.y),
+                                 v.right.max(vehicle.x), v.bottom.max(vehicle.y)),
+            None => Rect::new(vehicle.x, vehicle.y, vehicle.x, vehicle.y),
+        });
+    }
+    bounds.map(|bounds| Extent { centroid_x: sum_x / count as f64, centroid_y: sum_y / count as f64, bounds })
+}
+
+impl Default for WorldState {
+    fn default() -> Self {
+        WorldState::new()
+    }
+}